@@ -0,0 +1,68 @@
+//! Benchmarks `GridMaze::distances` against the previous implementation, which sorted its
+//! `pending` Vec on every pop and stored distances in a HashMap.
+//!
+//! Run with `cargo bench --bench distances`
+use std::collections::HashMap;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use mazes::generator::Algorithm;
+use mazes::maze::grid_maze::GridMaze;
+use mazes::maze::grid_node::GridNode;
+
+/// the previous implementation of `GridMaze::distances`, kept for comparison
+fn sorted_vec_distances(maze: &GridMaze, root: &GridNode) -> HashMap<GridNode, i32> {
+    let mut weights = HashMap::new();
+    weights.insert(*root, 0);
+    let mut pending = vec![*root];
+
+    while !pending.is_empty() {
+        pending.sort_unstable_by(|&an, &bn| maze[bn.pos()].weight().cmp(&maze[an.pos()].weight()));
+        let cur_node = pending.pop().unwrap();
+
+        for neighbor_node in maze.get_links(&cur_node) {
+            let total_weight = weights[&cur_node] + maze[neighbor_node.pos()].weight() as i32;
+            if weights.get(&neighbor_node).is_none_or(|&w| total_weight < w) {
+                pending.push(neighbor_node);
+                weights.insert(neighbor_node, total_weight);
+            }
+        }
+    }
+    weights
+}
+
+/// returns a braided `size` x `size` maze with random node weights. Braided mazes have many
+/// paths to each node, so they exercise the priority queue more than perfect mazes do
+fn weighted_braided_maze(size: usize) -> GridMaze {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut maze = Algorithm::RecursiveBacktracker.generate_with_rng(size, size, &mut rng).unwrap();
+    maze.braid(0.5);
+    for node in maze.iter_mut_nodes() {
+        node.set_weight(rng.gen_range(1, 10));
+    }
+    maze
+}
+
+fn distances(c: &mut Criterion) {
+    let mut group = c.benchmark_group("distances");
+    group.sample_size(10);
+
+    for size in [25, 50, 150, 1000].iter() {
+        let maze = weighted_braided_maze(*size);
+        let root = maze[0];
+
+        group.bench_with_input(BenchmarkId::new("binary_heap", size), &maze, |b, maze| {
+            b.iter(|| maze.distances(&root))
+        });
+        // the previous implementation slows down far faster, so only run it on small mazes
+        if *size <= 50 {
+            group.bench_with_input(BenchmarkId::new("sorted_vec", size), &maze, |b, maze| {
+                b.iter(|| sorted_vec_distances(maze, &root))
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, distances);
+criterion_main!(benches);
@@ -0,0 +1,34 @@
+//! Benchmarks generating a 2000 x 2000 maze with the recursive backtracker, and computing the
+//! distances from its north-west corner. These are the timings quoted when the allocation-free
+//! neighbor and link iterators were added to `GridMaze`.
+//!
+//! Run with `cargo bench --bench generate`
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use mazes::generator::Algorithm;
+
+const SIZE: usize = 2000;
+
+fn generate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("recursive_backtracker_2000");
+    group.sample_size(10);
+
+    group.bench_function("generate", |b| {
+        b.iter(|| {
+            Algorithm::RecursiveBacktracker
+                .generate_with_rng(SIZE, SIZE, StdRng::seed_from_u64(1))
+                .unwrap()
+        })
+    });
+
+    let maze = Algorithm::RecursiveBacktracker
+        .generate_with_rng(SIZE, SIZE, StdRng::seed_from_u64(1))
+        .unwrap();
+    let root = maze[0];
+    group.bench_function("distances", |b| b.iter(|| maze.distances(&root)));
+    group.finish();
+}
+
+criterion_group!(benches, generate);
+criterion_main!(benches);
@@ -17,7 +17,7 @@ fn main() {
     // going to use recursive backtracker as the starting maze. Any other algorithm that
     // generates a perfect maze would also work
     println!("recursive bactracker unbraided {}x{}", &height, &width);
-    let mut maze = recursive_backtracker::generate(height, width);
+    let mut maze = recursive_backtracker::generate(height, width).unwrap();
     println!("{}\n\n", &maze);
 
     println!("dead ends = {}", maze.dead_ends().len());
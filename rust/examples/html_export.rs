@@ -0,0 +1,24 @@
+use std::env;
+use std::fs;
+use mazes::generator::recursive_backtracker;
+use mazes::formats::html::to_html;
+
+fn main() {
+
+    // get width and height from STDIN else default them to 10 x 15
+    let args: Vec<String> = env::args().collect();
+    let (height, width) = match args.len() {
+        2 => (args[1].parse::<usize>().unwrap(), 10),
+        3 => (
+            args[1].parse::<usize>().unwrap(),
+            args[2].parse::<usize>().unwrap(),
+        ),
+        _ => (10, 15),
+    };
+
+    // generate the maze and write it to an interactive html page
+    let maze = recursive_backtracker::generate(height, width).unwrap();
+    let title = format!("recursive-backtracker {}x{}", &height, &width);
+    fs::write("maze.html", to_html(&maze, &title)).expect("could not write maze.html");
+    println!("wrote {} to maze.html", &title);
+}
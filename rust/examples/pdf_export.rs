@@ -0,0 +1,40 @@
+use std::env;
+use std::fs;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use mazes::generator::Steps;
+use mazes::generator::recursive_backtracker::RecursiveBacktracker;
+use mazes::formats::pdf::{to_pdf, PdfMaze, PdfOptions};
+
+fn main() {
+
+    // get width and height from STDIN else default them to 10 x 15
+    let args: Vec<String> = env::args().collect();
+    let (height, width) = match args.len() {
+        2 => (args[1].parse::<usize>().unwrap(), 10),
+        3 => (
+            args[1].parse::<usize>().unwrap(),
+            args[2].parse::<usize>().unwrap(),
+        ),
+        _ => (10, 15),
+    };
+
+    // generate four mazes from known seeds, so that they can be re-generated later
+    let seeds: Vec<u64> = (1..=4).collect();
+    let mazes: Vec<_> = seeds.iter()
+        .map(|seed| RecursiveBacktracker::new(height, width, StdRng::seed_from_u64(*seed)).unwrap().finish())
+        .collect();
+    let pdf_mazes: Vec<PdfMaze> = mazes.iter()
+        .zip(&seeds)
+        .map(|(maze, seed)| PdfMaze { maze, seed: Some(*seed) })
+        .collect();
+
+    // print two mazes per page, each page is followed by a page with the solutions
+    let options = PdfOptions {
+        title: Some(format!("recursive-backtracker {}x{}", &height, &width)),
+        rows: 2,
+        ..PdfOptions::default()
+    };
+    fs::write("mazes.pdf", to_pdf(&pdf_mazes, &options)).expect("could not write mazes.pdf");
+    println!("wrote {} mazes to mazes.pdf", pdf_mazes.len());
+}
@@ -19,12 +19,14 @@ fn main() {
 
     // generate the maze
     println!("prims {}x{}", &height, &width);
-    let maze = prims::generate(height, width);
+    let maze = prims::generate(height, width).unwrap();
     println!("{}", &maze);
 
     // find shortest path from northwest corner to southeast corner
     println!("prims {}x{} shortest path", &height, &width);
     let se_corner_idx = GridMaze::idx_1d(height - 1, width - 1, width);
-    let shortest_path = find_shortest_path(&maze, maze[0], maze[se_corner_idx]);
-    println!("{}\n\n\n", maze.display_path(&shortest_path));
+    match find_shortest_path(&maze, maze[0], maze[se_corner_idx]) {
+        Some(shortest_path) => println!("{}\n\n\n", maze.display_path(&shortest_path)),
+        None => println!("there is no path from the NW Corner to the SE Corner\n\n\n"),
+    }
 }
\ No newline at end of file
@@ -17,15 +17,16 @@ fn main() {
 
     // generate the maze
     println!("recursive-division {}x{}", &height, &width);
-    let maze = recursive_division::generate(height, width);
+    let maze = recursive_division::generate(height, width).unwrap();
     println!("{}", &maze);
 
-    // TODO find_shortest_path deadlocks in some cases, need to debug
     //find shortest path from northwest corner to southeast corner
     println!("recursive-division {}x{} shortest path from NW Corner to SE Corner", &height, &width);
     let sw_corner_idx = GridMaze::idx_1d(height - 1, width - 1, width);
-    let shortest_path = find_shortest_path(&maze, maze[0], maze[sw_corner_idx]);
-    println!("{}\n\n\n", maze.display_path(&shortest_path));
+    match find_shortest_path(&maze, maze[0], maze[sw_corner_idx]) {
+        Some(shortest_path) => println!("{}\n\n\n", maze.display_path(&shortest_path)),
+        None => println!("there is no path from the NW Corner to the SE Corner\n\n\n"),
+    }
 
     // example of printing the current distance values of the maze
     //let distances = maze.distances(&maze[0]);
@@ -18,12 +18,14 @@ fn main() {
 
     // generate the maze
     println!("sidewinder {}x{}", &height, &width);
-    let maze = sidewinder::generate(height, width);
+    let maze = sidewinder::generate(height, width).unwrap();
     println!("{}", &maze);
 
     // find shortest path from northwest corner to southwest corner
     println!("sidewinder {}x{} shortest path from NW Corner to SW Corner", &height, &width);
     let sw_corner_idx = GridMaze::idx_1d(height - 1, 0, width);
-    let shortest_path = find_shortest_path(&maze, maze[0], maze[sw_corner_idx]);
-    println!("{}\n\n\n", maze.display_path(&shortest_path));
+    match find_shortest_path(&maze, maze[0], maze[sw_corner_idx]) {
+        Some(shortest_path) => println!("{}\n\n\n", maze.display_path(&shortest_path)),
+        None => println!("there is no path from the NW Corner to the SE Corner\n\n\n"),
+    }
 }
\ No newline at end of file
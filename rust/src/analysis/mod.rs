@@ -0,0 +1,5 @@
+//! Modules for measuring the "texture" of mazes, so that the output of the generators can be
+//! compared with numbers instead of by feel
+
+/// computes statistics, such as the dead-end ratio and river factor, of a maze
+pub mod stats;
@@ -0,0 +1,266 @@
+use std::fmt::{Display, Formatter};
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::dijkstras::shortest_path;
+
+/// MazeStats holds metrics that describe the "texture" of a maze.
+///
+/// The degree of a node is the number of passages out of it, so a dead-end has degree 1, a node
+/// in the middle of a corridor has degree 2, and a junction has degree 3 or 4.
+/// Percentages and ratios are `0.0` when there is nothing to measure (i.e. the percentage of
+/// straightaways in a maze without any corridors).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MazeStats {
+    pub rows: usize,
+    pub cols: usize,
+    pub cells: usize,
+    /// the number of passages, a bi-directional link counts as one passage
+    pub passages: usize,
+    /// the number of nodes with degree 0, 1, 2, 3 and 4
+    pub degree_counts: [usize; 5],
+    /// the number of dead-ends, as returned by `GridMaze::dead_ends`
+    pub dead_ends: usize,
+    /// dead-ends as a fraction of all cells
+    pub dead_end_ratio: f64,
+    /// the number of nodes with three or more passages
+    pub junctions: usize,
+    /// the average number of moves between two nodes that are not in the middle of a corridor,
+    /// i.e. between dead-ends and junctions
+    pub average_corridor_length: f64,
+    /// the percentage of corridor nodes (degree 2) where the corridor goes straight through
+    pub straightaway_percent: f64,
+    /// the percentage of corridor nodes (degree 2) where the corridor turns
+    pub turn_percent: f64,
+    /// the number of nodes on the shortest path from the start to the goal, `None` if the goal
+    /// can't be reached
+    pub solution_length: Option<usize>,
+    /// `solution_length` as a fraction of all cells
+    pub solution_ratio: Option<f64>,
+    /// the average number of moves from a dead-end back to the junction it branches off.
+    /// A maze with a high river factor has few, long, winding dead-ends that "flow" like a river,
+    /// a low river factor means many short dead-ends
+    pub river_factor: f64,
+    /// the number of passages between east and west neighbors
+    pub horizontal_passages: usize,
+    /// the number of passages between north and south neighbors
+    pub vertical_passages: usize,
+    /// ranges from -1.0, when every passage is vertical, to 1.0, when every passage is horizontal.
+    /// An unbiased maze has a bias close to 0.0
+    pub horizontal_bias: f64,
+}
+
+/// returns the fraction `part / whole`, or 0.0 if `whole` is zero
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 { 0.0 } else { part as f64 / whole as f64 }
+}
+
+/// returns the passages out of `node`, i.e. the neighbors that `node` links to, in
+/// north, east, south, west order
+fn passages(maze: &GridMaze, node: &GridNode) -> Vec<GridNode> {
+    maze.neighbors(node)
+        .into_iter()
+        .filter(|neighbor| maze.has_link(node, neighbor))
+        .collect()
+}
+
+/// walks from `from` into `next` and along the corridor beyond it, until a node that is not in
+/// the middle of a corridor is reached. Returns the number of moves made, or `None` if the
+/// corridor loops around without ever reaching such a node
+fn corridor_length(maze: &GridMaze, degrees: &[usize], from: GridNode, next: GridNode) -> Option<usize> {
+    let (mut prev, mut cur) = (from, next);
+    let mut moves = 1;
+    while degrees[cur.pos()] == 2 {
+        let onward = passages(maze, &cur).into_iter().find(|n| *n != prev)?;
+        prev = cur;
+        cur = onward;
+        moves += 1;
+        if moves > maze.len() {
+            return None;
+        }
+    }
+    Some(moves)
+}
+
+/// computes the statistics of the `maze`. The solution is the shortest path from the maze's
+/// start to its goal.
+///
+/// The maze is expected to only have bi-directional links, as the generators produce. The degree
+/// of a node only counts the links out of it.
+pub fn analyze(maze: &GridMaze) -> MazeStats {
+    let (rows, cols) = maze.dimensions();
+    let cells = maze.len();
+    let degrees: Vec<usize> = maze.iter_nodes().map(|node| passages(maze, node).len()).collect();
+
+    let mut degree_counts = [0; 5];
+    for degree in degrees.iter() {
+        degree_counts[*degree] += 1;
+    }
+    let dead_ends = maze.dead_ends().len();
+    let junctions = degree_counts[3] + degree_counts[4];
+
+    let (mut horizontal_passages, mut vertical_passages) = (0, 0);
+    let (mut straight, mut turns) = (0, 0);
+    for node in maze.iter_nodes() {
+        let east_open = maze.east(node).is_some_and(|n| maze.has_link(node, &n) || maze.has_link(&n, node));
+        let south_open = maze.south(node).is_some_and(|n| maze.has_link(node, &n) || maze.has_link(&n, node));
+        horizontal_passages += east_open as usize;
+        vertical_passages += south_open as usize;
+
+        if degrees[node.pos()] == 2 {
+            let out = passages(maze, node);
+            let opposite = |a: Option<GridNode>, b: Option<GridNode>| {
+                matches!((a, b), (Some(a), Some(b)) if out.contains(&a) && out.contains(&b))
+            };
+            let is_straight = opposite(maze.north(node), maze.south(node))
+                || opposite(maze.east(node), maze.west(node));
+            if is_straight { straight += 1 } else { turns += 1 }
+        }
+    }
+    let passage_count = horizontal_passages + vertical_passages;
+
+    // every corridor is walked from both of its ends, which doesn't change the average
+    let corridors: Vec<usize> = maze.iter_nodes()
+        .filter(|node| degrees[node.pos()] != 2)
+        .flat_map(|node| passages(maze, node).into_iter().map(move |next| (*node, next)))
+        .filter_map(|(node, next)| corridor_length(maze, &degrees, node, next))
+        .collect();
+    let average_corridor_length = ratio(corridors.iter().sum(), corridors.len());
+
+    let branches: Vec<usize> = maze.iter_nodes()
+        .filter(|node| degrees[node.pos()] == 1)
+        .filter_map(|node| corridor_length(maze, &degrees, *node, passages(maze, node)[0]))
+        .collect();
+    let river_factor = ratio(branches.iter().sum(), branches.len());
+
+    let solution_length = shortest_path(maze, maze.start(), maze.goal()).map(|path| path.len());
+
+    MazeStats {
+        rows,
+        cols,
+        cells,
+        passages: passage_count,
+        degree_counts,
+        dead_ends,
+        dead_end_ratio: ratio(dead_ends, cells),
+        junctions,
+        average_corridor_length,
+        straightaway_percent: 100.0 * ratio(straight, straight + turns),
+        turn_percent: 100.0 * ratio(turns, straight + turns),
+        solution_length,
+        solution_ratio: solution_length.map(|len| ratio(len, cells)),
+        river_factor,
+        horizontal_passages,
+        vertical_passages,
+        horizontal_bias: ratio(horizontal_passages, passage_count) - ratio(vertical_passages, passage_count),
+    }
+}
+
+/// prints the statistics as a two column table
+impl Display for MazeStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let solution_length = self.solution_length.map_or(String::from("-"), |len| len.to_string());
+        let solution_ratio = self.solution_ratio.map_or(String::from("-"), |r| format!("{:.3}", r));
+        let rows = [
+            ("size", format!("{} x {}", self.rows, self.cols)),
+            ("cells", self.cells.to_string()),
+            ("passages", self.passages.to_string()),
+            ("dead-ends", self.dead_ends.to_string()),
+            ("dead-end ratio", format!("{:.3}", self.dead_end_ratio)),
+            ("junctions", self.junctions.to_string()),
+            ("nodes by degree (0/1/2/3/4)", format!(
+                "{}/{}/{}/{}/{}",
+                self.degree_counts[0], self.degree_counts[1], self.degree_counts[2],
+                self.degree_counts[3], self.degree_counts[4]
+            )),
+            ("average corridor length", format!("{:.2}", self.average_corridor_length)),
+            ("straightaways", format!("{:.1}%", self.straightaway_percent)),
+            ("turns", format!("{:.1}%", self.turn_percent)),
+            ("solution length", solution_length),
+            ("solution / cells", solution_ratio),
+            ("river factor", format!("{:.2}", self.river_factor)),
+            ("horizontal passages", self.horizontal_passages.to_string()),
+            ("vertical passages", self.vertical_passages.to_string()),
+            ("horizontal bias", format!("{:+.3}", self.horizontal_bias)),
+        ];
+
+        writeln!(f, "{:<28} {:>12}", "metric", "value")?;
+        writeln!(f, "{}", "-".repeat(41))?;
+        for (name, value) in rows.iter() {
+            writeln!(f, "{:<28} {:>12}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::analyze;
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn should_measure_a_single_corridor() {
+        // a 1x5 maze is one straight corridor
+        let mut maze = GridMaze::new(1, 5).unwrap();
+        for pos in 0..4 {
+            let (n1, n2) = (maze[pos], maze[pos + 1]);
+            maze.link(&n1, &n2, true);
+        }
+        let stats = analyze(&maze);
+        assert_eq!(stats.passages, 4);
+        assert_eq!(stats.degree_counts, [0, 2, 3, 0, 0]);
+        assert_eq!(stats.dead_ends, 2);
+        assert_eq!(stats.junctions, 0);
+        assert_eq!(stats.average_corridor_length, 4.0);
+        assert_eq!(stats.straightaway_percent, 100.0);
+        assert_eq!(stats.solution_length, Some(5));
+        assert_eq!(stats.solution_ratio, Some(1.0));
+        assert_eq!(stats.river_factor, 4.0);
+        assert_eq!(stats.horizontal_bias, 1.0);
+    }
+
+    #[test]
+    fn stats_should_be_consistent_for_every_generator() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let maze = algo.generate_with_rng(12, 12, StdRng::seed_from_u64(i as u64)).unwrap();
+            let stats = analyze(&maze);
+            assert_eq!(stats.degree_counts.iter().sum::<usize>(), 144, "{}", algo);
+            // perfect mazes are spanning trees
+            assert_eq!(stats.passages, 143, "{}", algo);
+            assert_eq!(stats.dead_ends, stats.degree_counts[1], "{}", algo);
+            assert!((stats.straightaway_percent + stats.turn_percent - 100.0).abs() < 1e-9);
+            assert!(stats.solution_length.is_some());
+            assert!(stats.to_string().contains("river factor"));
+        }
+    }
+
+    #[test]
+    fn should_measure_turns_and_bias() {
+        // an L shaped corridor, 0 -> 1 -> 3, that turns at node 1
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n3) = (maze[0], maze[1], maze[3]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n3, true);
+        let stats = analyze(&maze);
+        assert_eq!(stats.turn_percent, 100.0);
+        assert_eq!(stats.straightaway_percent, 0.0);
+        assert_eq!(stats.horizontal_bias, 0.0);
+        assert_eq!(stats.degree_counts, [1, 2, 1, 0, 0]);
+        assert_eq!(stats.solution_length, Some(3));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_serialize_stats() {
+        let maze = Algorithm::Wilsons.generate_with_rng(5, 5, StdRng::seed_from_u64(1)).unwrap();
+        let stats = analyze(&maze);
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"dead_end_ratio\""));
+        let loaded: super::MazeStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, stats);
+    }
+}
@@ -0,0 +1,352 @@
+//! maze-animate redraws a maze in the terminal while a generator carves it, then animates the
+//! solver flooding distances out from the maze's start and tracing the shortest path to its goal.
+//! Only ANSI escape codes are used for drawing, and `stty` is used to read
+//! single key presses, so it works in a plain Linux terminal.
+//!
+//! usage: `maze-animate [algorithm] [height] [width] [delay_ms]`
+//!
+//! keys: `space` pause/resume, `n` single step while paused, `+`/`-` change speed, `q` quit
+
+use std::collections::HashSet;
+use std::env;
+use std::fmt::Write as FmtWrite;
+use std::io::{self, Read, Write};
+use std::process::{self, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::thread_rng;
+use mazes::generator::{Algorithm, Event, Steps};
+use mazes::maze::grid_maze::GridMaze;
+use mazes::maze::grid_node::GridNode;
+use mazes::solver::dijkstras::shortest_path;
+
+// ANSI escape codes
+const CURSOR_HOME: &str = "\x1b[H";
+const CLEAR_SCREEN: &str = "\x1b[2J";
+const CLEAR_TO_END: &str = "\x1b[J";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+const RESET: &str = "\x1b[0m";
+const YELLOW_BG: &str = "\x1b[30;43m";
+const CYAN_BG: &str = "\x1b[30;46m";
+const GREEN_BG: &str = "\x1b[30;42m";
+const DIM: &str = "\x1b[2m";
+
+const MIN_DELAY_MS: u64 = 1;
+const MAX_DELAY_MS: u64 = 2000;
+
+/// how a single node of the maze should be drawn
+#[derive(Copy, Clone)]
+enum Style {
+    Plain,
+    // the node(s) touched by the most recent generator event
+    Current,
+    // a node reached by the solver, with its distance from the start
+    Flooded(i32),
+    // a node on the shortest path, with its distance from the start
+    Path(i32),
+}
+
+/// Terminal puts the terminal into "cbreak" mode, so that key presses can be read one at a time,
+/// and restores the original terminal settings when dropped
+struct Terminal {
+    saved_settings: Option<String>,
+}
+
+impl Terminal {
+    fn new() -> Self {
+        // save the current settings, then turn off line buffering, echo and signal keys
+        let saved_settings = stty(&["-g"])
+            .filter(|_| stty(&["-icanon", "-echo", "-isig", "min", "1"]).is_some());
+        print!("{}{}", HIDE_CURSOR, CLEAR_SCREEN);
+        Self { saved_settings }
+    }
+
+    /// returns true if key presses can be read from the terminal
+    fn is_interactive(&self) -> bool {
+        self.saved_settings.is_some()
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        if let Some(settings) = &self.saved_settings {
+            stty(&[settings.trim()]);
+        }
+        print!("{}", SHOW_CURSOR);
+        let _ = io::stdout().flush();
+    }
+}
+
+/// runs `stty` with `args` against the terminal connected to stdin, returning its output
+fn stty(args: &[&str]) -> Option<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+/// returns a receiver of key presses, read from stdin on a background thread
+fn spawn_key_reader() -> Receiver<u8> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for byte in io::stdin().lock().bytes() {
+            match byte {
+                Ok(b) if tx.send(b).is_ok() => {}
+                _ => break,
+            }
+        }
+    });
+    rx
+}
+
+/// Animation controls the speed of the animation, and handles the pause, step and quit keys
+struct Animation {
+    keys: Option<Receiver<u8>>,
+    delay: Duration,
+    paused: bool,
+    quit: bool,
+    frames: usize,
+}
+
+impl Animation {
+    /// waits for the current frame's delay to elapse, or for the user to single-step.
+    /// Returns false if the user wants to quit
+    fn wait(&mut self) -> bool {
+        self.frames += 1;
+        let keys = match &self.keys {
+            Some(keys) => keys,
+            None => {
+                thread::sleep(self.delay);
+                return true;
+            }
+        };
+
+        let deadline = Instant::now() + self.delay;
+        loop {
+            let key = if self.paused {
+                keys.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                keys.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            };
+
+            match key {
+                Ok(b' ') => self.paused = !self.paused,
+                Ok(b'n') | Ok(b's') if self.paused => return true,
+                Ok(b'+') | Ok(b'=') => {
+                    self.delay = (self.delay / 2).max(Duration::from_millis(MIN_DELAY_MS))
+                }
+                Ok(b'-') | Ok(b'_') => {
+                    self.delay = (self.delay * 2).min(Duration::from_millis(MAX_DELAY_MS))
+                }
+                // 'q' or ctrl-c
+                Ok(b'q') | Ok(0x03) => {
+                    self.quit = true;
+                    return false;
+                }
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => return true,
+                Err(RecvTimeoutError::Disconnected) => {
+                    // stdin was closed, keep animating without key controls
+                    self.keys = None;
+                    self.paused = false;
+                    return true;
+                }
+            }
+
+            if !self.paused && Instant::now() >= deadline {
+                return true;
+            }
+        }
+    }
+
+    /// returns the status line displayed beneath the maze
+    fn status(&self, phase: &str) -> String {
+        let state = if self.paused { "PAUSED" } else { "" };
+        format!(
+            "{} | frame {} | delay {}ms {}\n[space] pause  [n] step  [+/-] speed  [q] quit",
+            phase,
+            self.frames,
+            self.delay.as_millis(),
+            state
+        )
+    }
+}
+
+/// renders the `maze` using ANSI colors, in the same format as the `Display` impl of `GridMaze`.
+/// The `style` function determines how each node is drawn
+fn render<F: Fn(&GridNode) -> Style>(maze: &GridMaze, style: F) -> String {
+    let (_rows, cols) = maze.dimensions();
+    let mut buf = String::new();
+    let _ = writeln!(buf, "+{}", "----+".repeat(cols));
+
+    for row in maze.iter_rows() {
+        let mut top = String::from("|");
+        let mut bottom = String::from("+");
+
+        for cur_node in row.iter() {
+            let body = match style(cur_node) {
+                Style::Plain if maze.get_links(cur_node).is_empty() => format!("{}····{}", DIM, RESET),
+                Style::Plain => String::from("    "),
+                Style::Current => format!("{}    {}", YELLOW_BG, RESET),
+                Style::Flooded(dist) => format!("{}{:4x}{}", CYAN_BG, dist, RESET),
+                Style::Path(dist) => format!("{}{:4x}{}", GREEN_BG, dist, RESET),
+            };
+            top.push_str(&body);
+
+            match maze.east(cur_node) {
+                Some(east_node) if maze.has_link(cur_node, &east_node) => top.push(' '),
+                _ => top.push('|'),
+            }
+
+            match maze.south(cur_node) {
+                Some(south_node) if maze.has_link(cur_node, &south_node) => bottom.push_str("    +"),
+                _ => bottom.push_str("----+"),
+            }
+        }
+
+        let _ = writeln!(buf, "{}", top);
+        let _ = writeln!(buf, "{}", bottom);
+    }
+    buf
+}
+
+/// draws a frame: the rendered maze followed by the `status` line
+fn draw(frame: &str, status: &str) {
+    let mut out = io::stdout().lock();
+    let _ = write!(out, "{}{}{}{}", CURSOR_HOME, frame, status, CLEAR_TO_END);
+    let _ = out.flush();
+}
+
+/// returns the nodes that were touched by `event`
+fn event_nodes(event: &Event) -> Vec<GridNode> {
+    match *event {
+        Event::Visit(node) | Event::Backtrack(node) | Event::HuntScan(node) => vec![node],
+        Event::Link(node1, node2) | Event::Unlink(node1, node2) => vec![node1, node2],
+    }
+}
+
+/// animates the generation of a maze by `steps` and returns the finished maze, or `None` if the
+/// user quit
+fn animate_generation(anim: &mut Animation, mut steps: Box<dyn Steps>, algorithm: Algorithm) -> Option<GridMaze> {
+    let (height, width) = steps.maze().dimensions();
+    let phase = format!("generating: {} {}x{}", algorithm, height, width);
+
+    while let Some(event) = steps.next() {
+        let current: HashSet<GridNode> = event_nodes(&event).into_iter().collect();
+        let frame = render(steps.maze(), |node| {
+            if current.contains(node) { Style::Current } else { Style::Plain }
+        });
+        draw(&frame, &anim.status(&phase));
+        if !anim.wait() {
+            return None;
+        }
+    }
+
+    Some(steps.maze().clone())
+}
+
+/// animates the solver flooding distances from the start of the `maze`, followed by tracing the
+/// shortest path to its goal
+fn animate_solution(anim: &mut Animation, maze: &GridMaze) {
+    let start = maze.start();
+    let goal = maze.goal();
+    let distances = maze.distances(&start);
+
+    // flood outwards from the start, one "wave" of equally distant nodes per frame
+    let mut flood_order: Vec<(i32, GridNode)> = maze
+        .iter_nodes()
+        .filter_map(|node| distances.get(node).map(|dist| (*dist, *node)))
+        .collect();
+    flood_order.sort_by_key(|(dist, _)| *dist);
+
+    let mut flooded = 0;
+    while flooded < flood_order.len() {
+        let wave = flood_order[flooded].0;
+        while flooded < flood_order.len() && flood_order[flooded].0 == wave {
+            flooded += 1;
+        }
+        let reached: HashSet<GridNode> = flood_order[..flooded].iter().map(|(_, node)| *node).collect();
+        let frame = render(maze, |node| match distances.get(node) {
+            Some(dist) if reached.contains(node) => Style::Flooded(*dist),
+            _ => Style::Plain,
+        });
+        draw(&frame, &anim.status("solving: flooding distances"));
+        if !anim.wait() {
+            return;
+        }
+    }
+
+    // now trace the shortest path from the start to the goal, one node per frame
+    let path = match shortest_path(maze, start, goal) {
+        Some(path) => path,
+        None => return,
+    };
+
+    for traced in 1..=path.len() {
+        let on_path: HashSet<GridNode> = path.nodes()[..traced].iter().copied().collect();
+        let frame = render(maze, |node| match distances.get(node) {
+            Some(dist) if on_path.contains(node) => Style::Path(*dist),
+            Some(dist) => Style::Flooded(*dist),
+            None => Style::Plain,
+        });
+        draw(&frame, &anim.status("solving: tracing shortest path"));
+        if !anim.wait() {
+            return;
+        }
+    }
+}
+
+fn usage() -> ! {
+    let names: Vec<&str> = Algorithm::ALL.iter().map(|algo| algo.name()).collect();
+    eprintln!("usage: maze-animate [algorithm] [height] [width] [delay_ms]");
+    eprintln!("algorithms: {}", names.join(", "));
+    process::exit(1);
+}
+
+fn main() {
+    // get algorithm, height, width and delay from the command line, else default them
+    let args: Vec<String> = env::args().collect();
+    let algorithm = match args.get(1) {
+        Some(name) => name.parse::<Algorithm>().unwrap_or_else(|_| usage()),
+        None => Algorithm::RecursiveBacktracker,
+    };
+    let parse_arg = |idx: usize, default: u64| match args.get(idx) {
+        Some(arg) => arg.parse::<u64>().unwrap_or_else(|_| usage()),
+        None => default,
+    };
+    let height = parse_arg(2, 10) as usize;
+    let width = parse_arg(3, 15) as usize;
+    let delay = parse_arg(4, 50).clamp(MIN_DELAY_MS, MAX_DELAY_MS);
+    let steps = algorithm.steps(height, width, thread_rng()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        usage()
+    });
+
+    let terminal = Terminal::new();
+    let mut anim = Animation {
+        keys: if terminal.is_interactive() { Some(spawn_key_reader()) } else { None },
+        delay: Duration::from_millis(delay),
+        paused: false,
+        quit: false,
+        frames: 0,
+    };
+
+    if let Some(maze) = animate_generation(&mut anim, steps, algorithm) {
+        animate_solution(&mut anim, &maze);
+    }
+
+    if !anim.quit {
+        println!();
+    }
+    drop(terminal);
+}
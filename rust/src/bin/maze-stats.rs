@@ -0,0 +1,210 @@
+//! maze-stats generates a batch of seeded mazes with every algorithm, analyzes them, and reports
+//! the mean, standard deviation, range and a histogram of each metric, along with the time it took
+//! to generate the mazes. The report is written to stdout as CSV or JSON, so that the texture and
+//! the performance of the algorithms can be compared across releases.
+//!
+//! usage: `maze-stats [count] [height] [width] [seed] [csv|json]`
+//!
+//! The i-th maze of every algorithm is generated with the seed `seed + i`, so two runs with the
+//! same arguments analyze the same mazes (only the generation times will differ).
+
+use std::env;
+use std::fmt::Write;
+use std::process;
+use std::time::Instant;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use mazes::analysis::stats::{analyze, MazeStats};
+use mazes::generator::Algorithm;
+use mazes::maze::error::MazeError;
+
+/// the number of equal width bins in each histogram
+const BINS: usize = 10;
+
+/// reads a metric from the stats of a maze
+type Metric = fn(&MazeStats) -> Option<f64>;
+
+/// the metrics that are reported, along with how to read each one from the stats of a maze.
+/// A metric that is `None` for a maze (i.e. the solution length of a maze without a solution)
+/// is left out of its summary
+const METRICS: [(&str, Metric); 9] = [
+    ("dead_ends", |s| Some(s.dead_ends as f64)),
+    ("dead_end_ratio", |s| Some(s.dead_end_ratio)),
+    ("junctions", |s| Some(s.junctions as f64)),
+    ("average_corridor_length", |s| Some(s.average_corridor_length)),
+    ("straightaway_percent", |s| Some(s.straightaway_percent)),
+    ("solution_length", |s| s.solution_length.map(|len| len as f64)),
+    ("solution_ratio", |s| s.solution_ratio),
+    ("river_factor", |s| Some(s.river_factor)),
+    ("horizontal_bias", |s| Some(s.horizontal_bias)),
+];
+
+/// the name of the generation time metric, it isn't part of `MazeStats` so it is measured apart
+const GENERATION_MS: &str = "generation_ms";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+/// Summary holds the statistics of one metric, over all of the mazes generated by one algorithm
+struct Summary {
+    samples: usize,
+    mean: f64,
+    std_dev: f64,
+    min: f64,
+    max: f64,
+    /// counts of the samples that fall into `BINS` equal width bins between `min` and `max`
+    histogram: [usize; BINS],
+}
+
+impl Summary {
+    /// summarizes the `values`, the standard deviation is the sample standard deviation.
+    /// Returns `None` if there are no values
+    fn new(values: &[f64]) -> Option<Summary> {
+        if values.is_empty() {
+            return None;
+        }
+        let samples = values.len();
+        let mean = values.iter().sum::<f64>() / samples as f64;
+        let std_dev = if samples > 1 {
+            let squares: f64 = values.iter().map(|v| (v - mean) * (v - mean)).sum();
+            (squares / (samples - 1) as f64).sqrt()
+        } else {
+            0.0
+        };
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+        // the maximum value goes in the last bin, and every value goes in the first bin when
+        // they are all the same
+        let mut histogram = [0; BINS];
+        let width = (max - min) / BINS as f64;
+        for value in values {
+            let bin = if width > 0.0 { ((value - min) / width) as usize } else { 0 };
+            histogram[bin.min(BINS - 1)] += 1;
+        }
+
+        Some(Summary { samples, mean, std_dev, min, max, histogram })
+    }
+}
+
+/// the summaries of every metric, in the order of `METRICS`, followed by the generation time
+struct Report {
+    algorithm: Algorithm,
+    metrics: Vec<(&'static str, Summary)>,
+}
+
+/// generates `count` mazes with the `algorithm` and summarizes their metrics
+fn measure(algorithm: Algorithm, count: usize, height: usize, width: usize, seed: u64) -> Result<Report, MazeError> {
+    let mut stats = Vec::with_capacity(count);
+    let mut times = Vec::with_capacity(count);
+    for i in 0..count {
+        let rng = StdRng::seed_from_u64(seed.wrapping_add(i as u64));
+        let started = Instant::now();
+        let maze = algorithm.generate_with_rng(height, width, rng)?;
+        times.push(started.elapsed().as_secs_f64() * 1000.0);
+        stats.push(analyze(&maze));
+    }
+
+    let mut metrics: Vec<(&'static str, Summary)> = METRICS
+        .iter()
+        .filter_map(|(name, metric)| {
+            let values: Vec<f64> = stats.iter().filter_map(metric).collect();
+            Summary::new(&values).map(|summary| (*name, summary))
+        })
+        .collect();
+    if let Some(summary) = Summary::new(&times) {
+        metrics.push((GENERATION_MS, summary));
+    }
+
+    Ok(Report { algorithm, metrics })
+}
+
+fn join_histogram(histogram: &[usize], sep: &str) -> String {
+    histogram.iter().map(|count| count.to_string()).collect::<Vec<String>>().join(sep)
+}
+
+/// writes one row per algorithm and metric. The histogram is a single column of
+/// semicolon separated bin counts
+fn to_csv(reports: &[Report], count: usize, height: usize, width: usize, seed: u64) -> String {
+    let mut buf = String::from("algorithm,rows,cols,count,seed,metric,samples,mean,std_dev,min,max,histogram\n");
+    for report in reports {
+        for (name, s) in report.metrics.iter() {
+            let _ = writeln!(
+                buf,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                report.algorithm, height, width, count, seed, name,
+                s.samples, s.mean, s.std_dev, s.min, s.max, join_histogram(&s.histogram, ";")
+            );
+        }
+    }
+    buf
+}
+
+/// writes the reports as a JSON object. The names of algorithms and metrics never need escaping,
+/// and all of the numbers are finite, so the JSON is written by hand
+fn to_json(reports: &[Report], count: usize, height: usize, width: usize, seed: u64) -> String {
+    let mut buf = String::new();
+    let _ = writeln!(buf, "{{");
+    let _ = writeln!(buf, "  \"rows\": {},\n  \"cols\": {},\n  \"count\": {},\n  \"seed\": {},", height, width, count, seed);
+    let _ = writeln!(buf, "  \"bins\": {},\n  \"algorithms\": [", BINS);
+    for (i, report) in reports.iter().enumerate() {
+        let _ = writeln!(buf, "    {{\n      \"name\": \"{}\",\n      \"metrics\": {{", report.algorithm);
+        for (j, (name, s)) in report.metrics.iter().enumerate() {
+            let _ = write!(
+                buf,
+                "        \"{}\": {{\"samples\": {}, \"mean\": {}, \"std_dev\": {}, \"min\": {}, \"max\": {}, \"histogram\": [{}]}}",
+                name, s.samples, s.mean, s.std_dev, s.min, s.max, join_histogram(&s.histogram, ", ")
+            );
+            let _ = writeln!(buf, "{}", if j + 1 < report.metrics.len() { "," } else { "" });
+        }
+        let _ = writeln!(buf, "      }}\n    }}{}", if i + 1 < reports.len() { "," } else { "" });
+    }
+    let _ = writeln!(buf, "  ]\n}}");
+    buf
+}
+
+fn usage() -> ! {
+    eprintln!("usage: maze-stats [count] [height] [width] [seed] [csv|json]");
+    eprintln!("generates `count` mazes of every algorithm and reports statistics of their metrics");
+    process::exit(1);
+}
+
+fn main() {
+    // get count, height, width, seed and format from the command line, else default them
+    let args: Vec<String> = env::args().collect();
+    let parse_arg = |idx: usize, default: u64| match args.get(idx) {
+        Some(arg) => arg.parse::<u64>().unwrap_or_else(|_| usage()),
+        None => default,
+    };
+    let count = parse_arg(1, 100) as usize;
+    let height = parse_arg(2, 20) as usize;
+    let width = parse_arg(3, 20) as usize;
+    let seed = parse_arg(4, 0);
+    let format = match args.get(5).map(|arg| arg.to_lowercase()) {
+        None => Format::Csv,
+        Some(arg) if arg == "csv" => Format::Csv,
+        Some(arg) if arg == "json" => Format::Json,
+        Some(_) => usage(),
+    };
+    if count == 0 {
+        usage();
+    }
+
+    let reports: Vec<Report> = Algorithm::ALL
+        .iter()
+        .map(|algo| measure(*algo, count, height, width, seed))
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            usage()
+        });
+
+    match format {
+        Format::Csv => print!("{}", to_csv(&reports, count, height, width, seed)),
+        Format::Json => print!("{}", to_json(&reports, count, height, width, seed)),
+    }
+}
@@ -0,0 +1,236 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::maze::grid_maze::GridMaze;
+
+/// ParseError is returned when text can't be parsed into a GridMaze. Lines and columns are
+/// numbered from 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// the text does not contain a maze
+    Empty,
+    /// the character at `line` and `column` is not one of the `expected` characters
+    UnexpectedChar { line: usize, column: usize, found: char, expected: &'static str },
+    /// `line` does not have the same length as the top wall of the maze
+    LineLength { line: usize, len: usize, expected: usize },
+    /// the maze ends before the southern walls of its last row, which should be on `line`
+    MissingLine { line: usize },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "the text does not contain a maze"),
+            ParseError::UnexpectedChar { line, column, found, expected } => write!(
+                f,
+                "line {}, column {}: expected {} but found {:?}",
+                line, column, expected, found
+            ),
+            ParseError::LineLength { line, len, expected } => write!(
+                f,
+                "line {}: expected {} characters but found {}",
+                line, expected, len
+            ),
+            ParseError::MissingLine { line } => {
+                write!(f, "line {}: expected the southern walls of the last row", line)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// In this impl block are functions specific to reading a GridMaze from text
+impl GridMaze {
+
+    /// parses a maze drawn in the format written by the `Display` impl of GridMaze, i.e.
+    ///
+    /// ```text
+    /// +----+----+
+    /// |         |
+    /// +    +----+
+    /// |         |
+    /// +----+----+
+    /// ```
+    ///
+    /// The bodies of the cells may also contain hexadecimal digits, so the output of
+    /// `GridMaze::display_path` can be parsed as well. Trailing whitespace, and blank lines before
+    /// or after the maze, are ignored. Every opening in a wall becomes a bi-directional link
+    /// between the two nodes on either side of it.
+    pub fn from_ascii(text: &str) -> Result<GridMaze, ParseError> {
+        let lines: Vec<(usize, Vec<char>)> = text.lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line.trim_end().chars().collect()))
+            .collect();
+        let first = lines.iter().position(|(_, line)| !line.is_empty()).ok_or(ParseError::Empty)?;
+        let last = lines.iter().rposition(|(_, line)| !line.is_empty()).unwrap_or(first);
+        let lines = &lines[first..=last];
+
+        // the top wall determines the number of columns
+        let (line_no, top) = &lines[0];
+        expect(*line_no, top, 0, "+", "'+'")?;
+        for column in 1..top.len() {
+            if column % 5 == 0 {
+                expect(*line_no, top, column, "+", "'+'")?;
+            } else {
+                expect(*line_no, top, column, "-", "'-'")?;
+            }
+        }
+        let expected_len = top.len();
+        if expected_len % 5 != 1 {
+            return Err(ParseError::LineLength {
+                line: *line_no,
+                len: expected_len,
+                expected: expected_len + 5 - (expected_len - 1) % 5,
+            });
+        }
+        let cols = (expected_len - 1) / 5;
+
+        if lines.len().is_multiple_of(2) {
+            return Err(ParseError::MissingLine { line: lines[lines.len() - 1].0 + 1 });
+        }
+        let rows = (lines.len() - 1) / 2;
+        for (line_no, line) in lines.iter() {
+            if line.len() != expected_len {
+                return Err(ParseError::LineLength { line: *line_no, len: line.len(), expected: expected_len });
+            }
+        }
+
+        // a top wall of a single '+' has no cells below it
+        let mut maze = GridMaze::new(rows, cols).map_err(|_| ParseError::Empty)?;
+        for row in 0..rows {
+            // the line holding the cell bodies and their eastern walls
+            let (line_no, line) = &lines[1 + 2 * row];
+            expect(*line_no, line, 0, "|", "'|'")?;
+            for col in 0..cols {
+                let start = 1 + 5 * col;
+                for column in start..start + 4 {
+                    expect(*line_no, line, column, " 0123456789abcdefABCDEF", "' ' or a hex digit")?;
+                }
+                let east_wall = if col + 1 == cols {
+                    expect(*line_no, line, start + 4, "|", "'|'")?
+                } else {
+                    expect(*line_no, line, start + 4, "| ", "'|' or ' '")?
+                };
+                if east_wall == ' ' {
+                    let node = maze[GridMaze::idx_1d(row, col, cols)];
+                    let east_node = maze[GridMaze::idx_1d(row, col + 1, cols)];
+                    maze.link(&node, &east_node, true);
+                }
+            }
+
+            // the line holding the southern walls of the cells
+            let (line_no, line) = &lines[2 + 2 * row];
+            expect(*line_no, line, 0, "+", "'+'")?;
+            for col in 0..cols {
+                let start = 1 + 5 * col;
+                let south_wall = if row + 1 == rows {
+                    expect(*line_no, line, start, "-", "'-'")?
+                } else {
+                    expect(*line_no, line, start, "- ", "'-' or ' '")?
+                };
+                // the rest of the wall must match its first character
+                let (allowed, expected) = if south_wall == '-' { ("-", "'-'") } else { (" ", "' '") };
+                for column in start + 1..start + 4 {
+                    expect(*line_no, line, column, allowed, expected)?;
+                }
+                expect(*line_no, line, start + 4, "+", "'+'")?;
+
+                if south_wall == ' ' {
+                    let node = maze[GridMaze::idx_1d(row, col, cols)];
+                    let south_node = maze[GridMaze::idx_1d(row + 1, col, cols)];
+                    maze.link(&node, &south_node, true);
+                }
+            }
+        }
+
+        Ok(maze)
+    }
+}
+
+/// returns the character at the 0-based `column` of `line`, if it is one of the `allowed` characters
+fn expect(
+    line_no: usize,
+    line: &[char],
+    column: usize,
+    allowed: &str,
+    expected: &'static str,
+) -> Result<char, ParseError> {
+    match line.get(column) {
+        Some(&c) if allowed.contains(c) => Ok(c),
+        Some(&found) => Err(ParseError::UnexpectedChar { line: line_no, column: column + 1, found, expected }),
+        None => Err(ParseError::LineLength { line: line_no, len: line.len(), expected: column + 1 }),
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::ParseError;
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn every_generator_should_round_trip_through_display() {
+        for algo in Algorithm::ALL.iter() {
+            let maze = algo.generate_with_rng(7, 9, StdRng::seed_from_u64(5)).unwrap();
+            let parsed = GridMaze::from_ascii(&maze.to_string()).unwrap();
+            assert_eq!(parsed.dimensions(), (7, 9));
+            assert_eq!(parsed.to_string(), maze.to_string(), "{} did not round trip", algo);
+        }
+    }
+
+    #[test]
+    fn should_parse_display_path_output() {
+        let maze = Algorithm::Wilsons.generate_with_rng(6, 6, StdRng::seed_from_u64(8)).unwrap();
+        let distances = maze.distances(&maze[0]);
+        let parsed = GridMaze::from_ascii(&maze.display_path(&distances)).unwrap();
+        assert_eq!(parsed.to_string(), maze.to_string());
+    }
+
+    #[test]
+    fn should_parse_hand_drawn_maze() {
+        let text = "
+            +----+----+----+
+            |         |    |
+            +----+    +    +
+            |              |
+            +----+----+----+
+        ";
+        let unindented: String = text.lines().map(|l| format!("{}\n", l.trim_start())).collect();
+        let maze = GridMaze::from_ascii(&unindented).unwrap();
+        assert_eq!(maze.dimensions(), (2, 3));
+        assert!(maze.has_link(&maze[0], &maze[1]));
+        assert!(maze.has_link(&maze[1], &maze[0]));
+        assert!(!maze.has_link(&maze[1], &maze[2]));
+        assert!(maze.has_link(&maze[1], &maze[4]));
+        assert!(maze.has_link(&maze[2], &maze[5]));
+        assert!(!maze.has_link(&maze[0], &maze[3]));
+    }
+
+    #[test]
+    fn should_report_line_and_column_of_malformed_walls() {
+        let text = "+----+----+\n|    *    |\n+----+----+\n";
+        assert_eq!(
+            GridMaze::from_ascii(text).unwrap_err(),
+            ParseError::UnexpectedChar { line: 2, column: 6, found: '*', expected: "'|' or ' '" }
+        );
+
+        let text = "+----+----+\n|    |    |\n+-- -+----+\n";
+        assert_eq!(
+            GridMaze::from_ascii(text).unwrap_err(),
+            ParseError::UnexpectedChar { line: 3, column: 4, found: ' ', expected: "'-'" }
+        );
+
+        let text = "+----+----+\n|    |    |\n+----+---+\n";
+        assert_eq!(
+            GridMaze::from_ascii(text).unwrap_err(),
+            ParseError::LineLength { line: 3, len: 10, expected: 11 }
+        );
+
+        let text = "+----+----+\n|    |    |\n";
+        assert_eq!(GridMaze::from_ascii(text).unwrap_err(), ParseError::MissingLine { line: 3 });
+        assert_eq!(GridMaze::from_ascii(" \n\n").unwrap_err(), ParseError::Empty);
+    }
+}
@@ -0,0 +1,365 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
+use crate::maze::grid_node::GridNode;
+
+/// every encoded maze starts with these bytes
+pub const MAGIC: [u8; 4] = *b"MAZE";
+
+/// the current version of the binary format
+pub const VERSION: u8 = 1;
+
+/// flag that is set when the header contains a seed
+const FLAG_SEED: u8 = 0b0000_0001;
+
+/// length of the header, not including the optional seed
+const HEADER_LEN: usize = 15;
+
+/// length of the checksum at the end of the encoded maze
+const CHECKSUM_LEN: usize = 4;
+
+/// Topology identifies the shape of an encoded maze
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Topology {
+    /// a rectangular grid where each cell has up to four neighbors (i.e. a `GridMaze`)
+    Grid,
+}
+
+impl Topology {
+    /// returns the byte used for this topology in the header
+    pub(crate) fn to_byte(self) -> u8 {
+        match self {
+            Topology::Grid => 0,
+        }
+    }
+
+    /// returns the topology with the header byte `b`
+    pub(crate) fn from_byte(b: u8) -> Option<Topology> {
+        match b {
+            0 => Some(Topology::Grid),
+            _ => None,
+        }
+    }
+}
+
+/// Header holds the information, other than the links, stored in an encoded maze
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub version: u8,
+    pub topology: Topology,
+    pub rows: usize,
+    pub cols: usize,
+    /// the seed that was used to generate the maze, if it was given to `encode`
+    pub seed: Option<u64>,
+}
+
+/// EncodeError is returned when a maze can't be stored in the binary format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeError {
+    /// the maze has more than `u32::MAX` rows or columns
+    TooLarge { rows: usize, cols: usize },
+    /// the link from `from` to `to` is one-way, or between nodes that are not neighbors
+    UnsupportedLink { from: usize, to: usize },
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EncodeError::TooLarge { rows, cols } => {
+                write!(f, "a {}x{} maze is too large to encode", rows, cols)
+            }
+            EncodeError::UnsupportedLink { from, to } => write!(
+                f,
+                "the link from {} to {} is not a bi-directional link between neighbors",
+                from, to
+            ),
+        }
+    }
+}
+
+impl Error for EncodeError {}
+
+/// DecodeError is returned when bytes can't be decoded into a maze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// the bytes are shorter than the header and checksum
+    TooShort { len: usize },
+    /// the bytes do not start with `MAGIC`
+    BadMagic,
+    UnsupportedVersion(u8),
+    UnknownTopology(u8),
+    /// the header has flags set that this version does not know about
+    UnknownFlags(u8),
+    /// the length of the bytes does not match the length given by the header
+    LengthMismatch { expected: usize, found: usize },
+    ChecksumMismatch { expected: u32, found: u32 },
+    /// the cell at `pos` links east, or south, out of the maze
+    InvalidLink { pos: usize },
+    /// the rows and cols in the header can't make a maze
+    InvalidDimensions(MazeError),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::TooShort { len } => write!(f, "{} bytes is too short to be a maze", len),
+            DecodeError::BadMagic => write!(f, "the bytes are not an encoded maze"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            DecodeError::UnknownTopology(t) => write!(f, "unknown topology {}", t),
+            DecodeError::UnknownFlags(flags) => write!(f, "unknown header flags {:#04x}", flags),
+            DecodeError::LengthMismatch { expected, found } => {
+                write!(f, "expected {} bytes but found {}", expected, found)
+            }
+            DecodeError::ChecksumMismatch { expected, found } => {
+                write!(f, "checksum mismatch, expected {:#010x} but found {:#010x}", expected, found)
+            }
+            DecodeError::InvalidLink { pos } => write!(f, "cell {} links out of the maze", pos),
+            DecodeError::InvalidDimensions(e) => write!(f, "invalid dimensions, {}", e),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+impl From<MazeError> for DecodeError {
+    fn from(e: MazeError) -> Self {
+        DecodeError::InvalidDimensions(e)
+    }
+}
+
+/// encodes the `maze` into a compact binary format, optionally storing the `seed` that was used
+/// to generate it.
+///
+/// Only the east and south link of each cell is stored, using two bits per cell, so the maze
+/// must only contain bi-directional links between neighboring cells. This is true of every maze
+/// produced by the generators, including braided mazes. Node weights are not stored.
+///
+/// The format is (all integers are little-endian):
+///
+/// | bytes | contents |
+/// |-------|----------|
+/// | 4     | magic number, the ASCII bytes `MAZE` |
+/// | 1     | version, currently 1 |
+/// | 1     | topology, 0 = grid |
+/// | 1     | flags, bit 0 is set if a seed is present |
+/// | 4     | rows (u32) |
+/// | 4     | cols (u32) |
+/// | 8     | seed (u64), only present if flag bit 0 is set |
+/// | ⌈rows·cols/4⌉ | link bits, bit 2·i is the east link and bit 2·i+1 the south link of cell i, least significant bit first |
+/// | 4     | CRC-32 (IEEE) of all the preceding bytes |
+pub fn encode(maze: &GridMaze, seed: Option<u64>) -> Result<Vec<u8>, EncodeError> {
+    let (rows, cols) = maze.dimensions();
+    if rows > u32::MAX as usize || cols > u32::MAX as usize {
+        return Err(EncodeError::TooLarge { rows, cols });
+    }
+
+    let mut bytes = Vec::with_capacity(HEADER_LEN + 8 + link_bytes_len(maze.len()) + CHECKSUM_LEN);
+    bytes.extend_from_slice(&MAGIC);
+    bytes.push(VERSION);
+    bytes.push(Topology::Grid.to_byte());
+    bytes.push(if seed.is_some() { FLAG_SEED } else { 0 });
+    bytes.extend_from_slice(&(rows as u32).to_le_bytes());
+    bytes.extend_from_slice(&(cols as u32).to_le_bytes());
+    if let Some(seed) = seed {
+        bytes.extend_from_slice(&seed.to_le_bytes());
+    }
+
+    bytes.extend_from_slice(&pack_links(maze)?);
+
+    let checksum = crc32(&bytes);
+    bytes.extend_from_slice(&checksum.to_le_bytes());
+    Ok(bytes)
+}
+
+/// decodes a maze that was encoded by `encode`, returning the header and the maze
+pub fn decode(bytes: &[u8]) -> Result<(Header, GridMaze), DecodeError> {
+    if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+        return Err(DecodeError::TooShort { len: bytes.len() });
+    }
+    if bytes[0..4] != MAGIC {
+        return Err(DecodeError::BadMagic);
+    }
+    let version = bytes[4];
+    if version != VERSION {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+    let topology = Topology::from_byte(bytes[5]).ok_or(DecodeError::UnknownTopology(bytes[5]))?;
+    let flags = bytes[6];
+    if flags & !FLAG_SEED != 0 {
+        return Err(DecodeError::UnknownFlags(flags));
+    }
+    let rows = read_u32(&bytes[7..11]) as usize;
+    let cols = read_u32(&bytes[11..15]) as usize;
+
+    let seed_len = if flags & FLAG_SEED != 0 { 8 } else { 0 };
+    let len = rows.checked_mul(cols).ok_or(DecodeError::LengthMismatch {
+        expected: usize::MAX,
+        found: bytes.len(),
+    })?;
+    let expected_len = HEADER_LEN + seed_len + link_bytes_len(len) + CHECKSUM_LEN;
+    if bytes.len() != expected_len {
+        return Err(DecodeError::LengthMismatch { expected: expected_len, found: bytes.len() });
+    }
+
+    let (body, checksum_bytes) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+    let expected = read_u32(checksum_bytes);
+    let found = crc32(body);
+    if expected != found {
+        return Err(DecodeError::ChecksumMismatch { expected, found });
+    }
+
+    let seed = if seed_len > 0 {
+        let mut seed_bytes = [0u8; 8];
+        seed_bytes.copy_from_slice(&body[HEADER_LEN..HEADER_LEN + 8]);
+        Some(u64::from_le_bytes(seed_bytes))
+    } else {
+        None
+    };
+
+    let maze = unpack_links(rows, cols, &body[HEADER_LEN + seed_len..])?;
+
+    let header = Header { version, topology, rows, cols, seed };
+    Ok((header, maze))
+}
+
+/// packs the east and south links of every node of the `maze` into two bits per cell. Bit 2·i
+/// is the east link and bit 2·i+1 the south link of cell i, least significant bit first
+pub(crate) fn pack_links(maze: &GridMaze) -> Result<Vec<u8>, EncodeError> {
+    let mut bytes = vec![0u8; link_bytes_len(maze.len())];
+    for node in maze.iter_nodes() {
+        for linked in maze.get_links(node) {
+            let is_neighbor = [maze.north(node), maze.east(node), maze.south(node), maze.west(node)]
+                .contains(&Some(linked));
+            if !is_neighbor || !maze.has_link(&linked, node) {
+                return Err(EncodeError::UnsupportedLink { from: node.pos(), to: linked.pos() });
+            }
+        }
+
+        let bit = 2 * node.pos();
+        if linked_to(maze, node, maze.east(node)) {
+            bytes[bit / 8] |= 1 << (bit % 8);
+        }
+        if linked_to(maze, node, maze.south(node)) {
+            bytes[(bit + 1) / 8] |= 1 << ((bit + 1) % 8);
+        }
+    }
+    Ok(bytes)
+}
+
+/// builds a `rows` by `cols` maze from the link bits written by `pack_links`. `links` must hold
+/// exactly `link_bytes_len(rows * cols)` bytes
+pub(crate) fn unpack_links(rows: usize, cols: usize, links: &[u8]) -> Result<GridMaze, DecodeError> {
+    let mut maze = GridMaze::new(rows, cols)?;
+    for pos in 0..maze.len() {
+        let node = maze[pos];
+        let bit = 2 * pos;
+        let links_east = links[bit / 8] & (1 << (bit % 8)) != 0;
+        let links_south = links[(bit + 1) / 8] & (1 << ((bit + 1) % 8)) != 0;
+
+        if links_east {
+            let east_node = maze.east(&node).ok_or(DecodeError::InvalidLink { pos })?;
+            maze.link(&node, &east_node, true);
+        }
+        if links_south {
+            let south_node = maze.south(&node).ok_or(DecodeError::InvalidLink { pos })?;
+            maze.link(&node, &south_node, true);
+        }
+    }
+    Ok(maze)
+}
+
+/// returns true if `node` links to `neighbor`
+fn linked_to(maze: &GridMaze, node: &GridNode, neighbor: Option<GridNode>) -> bool {
+    neighbor.is_some_and(|neighbor| maze.has_link(node, &neighbor))
+}
+
+/// returns the number of bytes needed to store two link bits for `cells` cells
+pub(crate) fn link_bytes_len(cells: usize) -> usize {
+    cells.div_ceil(4)
+}
+
+/// reads a little-endian u32 from the first four bytes of `bytes`
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// computes the CRC-32 (IEEE 802.3) checksum of `bytes`
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{encode, decode, crc32, DecodeError, EncodeError, Topology};
+    use crate::generator::Algorithm;
+    use crate::generator::recursive_backtracker;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn crc32_should_match_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn every_generator_should_round_trip() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let seed = i as u64 * 31;
+            let maze = algo.generate_with_rng(9, 13, StdRng::seed_from_u64(seed)).unwrap();
+            let bytes = encode(&maze, Some(seed)).unwrap();
+            // header, seed, 2 bits for each of the 117 cells and the checksum
+            assert_eq!(bytes.len(), 15 + 8 + 30 + 4);
+
+            let (header, decoded) = decode(&bytes).unwrap();
+            assert_eq!(header.seed, Some(seed));
+            assert_eq!(header.topology, Topology::Grid);
+            assert_eq!((header.rows, header.cols), (9, 13));
+            assert_eq!(decoded.to_string(), maze.to_string(), "{} did not round trip", algo);
+            for node in maze.iter_nodes() {
+                let mut expected: Vec<usize> = maze.get_links(node).iter().map(|n| n.pos()).collect();
+                let mut found: Vec<usize> = decoded.get_links(node).iter().map(|n| n.pos()).collect();
+                expected.sort_unstable();
+                found.sort_unstable();
+                assert_eq!(expected, found, "{} links of node {} differ", algo, node.pos());
+            }
+        }
+    }
+
+    #[test]
+    fn braided_maze_should_round_trip() {
+        let mut maze = recursive_backtracker::generate(10, 10).unwrap();
+        maze.braid(1.0);
+        let (header, decoded) = decode(&encode(&maze, None).unwrap()).unwrap();
+        assert_eq!(header.seed, None);
+        assert_eq!(decoded.to_string(), maze.to_string());
+    }
+
+    #[test]
+    fn should_reject_one_way_links() {
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1) = (maze[0], maze[1]);
+        maze.link(&n0, &n1, false);
+        assert_eq!(encode(&maze, None), Err(EncodeError::UnsupportedLink { from: 0, to: 1 }));
+    }
+
+    #[test]
+    fn should_detect_corruption() {
+        let maze = recursive_backtracker::generate(4, 4).unwrap();
+        let mut bytes = encode(&maze, None).unwrap();
+        bytes[16] ^= 0b0100;
+        assert!(matches!(decode(&bytes), Err(DecodeError::ChecksumMismatch { .. })));
+        assert_eq!(decode(&bytes[..10]).unwrap_err(), DecodeError::TooShort { len: 10 });
+        assert_eq!(decode(b"NOPE-not-a-maze-at-all").unwrap_err(), DecodeError::BadMagic);
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::Write;
+use crate::maze::grid_maze::GridMaze;
+use crate::solver::distances::Distances;
+
+/// distance between the centers of two neighboring cells, in points
+const CELL_SPACING: usize = 72;
+
+/// returns the `maze` as a GraphViz DOT graph.
+///
+/// Every node has a `pos` attribute holding the position of its cell (with the north-west cell at
+/// the top left), so `neato -n` lays the graph out as a grid:
+///
+/// ```text
+/// neato -n -Tsvg maze.dot > maze.svg
+/// ```
+///
+/// Nodes also have `row`, `col` and `weight` attributes and, if `distances` is given, a
+/// `distance` attribute for every node that has a distance. Bi-directional links are written
+/// as a single edge, one-way links are written with `dir="forward"`.
+pub fn to_dot(maze: &GridMaze, distances: Option<&Distances>) -> String {
+    let (rows, cols) = maze.dimensions();
+    let mut buf = String::new();
+    let _ = writeln!(buf, "graph maze {{");
+    let _ = writeln!(buf, "    graph [rows={}, cols={}];", rows, cols);
+    let _ = writeln!(buf, "    node [shape=circle, width=0.3, fixedsize=true];");
+
+    for node in maze.iter_nodes() {
+        let (row, col) = (node.pos() / cols, node.pos() % cols);
+        let x = col * CELL_SPACING;
+        let y = (rows - 1 - row) * CELL_SPACING;
+        let _ = write!(
+            buf,
+            "    n{} [pos=\"{},{}!\", row={}, col={}, weight={}",
+            node.pos(), x, y, row, col, node.weight()
+        );
+        if let Some(dist) = distances.and_then(|d| d.get(node)) {
+            let _ = write!(buf, ", distance={}", dist);
+        }
+        buf.push_str("];\n");
+    }
+
+    for (source, target, bi_link) in edges(maze) {
+        if bi_link {
+            let _ = writeln!(buf, "    n{} -- n{};", source, target);
+        } else {
+            let _ = writeln!(buf, "    n{} -- n{} [dir=\"forward\"];", source, target);
+        }
+    }
+
+    buf.push_str("}\n");
+    buf
+}
+
+/// returns the links of the `maze` as `(source, target, bi_link)` edges, ordered by source and
+/// then target. A bi-directional link is only returned once, with the lower position as its source
+pub(crate) fn edges(maze: &GridMaze) -> Vec<(usize, usize, bool)> {
+    let mut edges = vec![];
+    for node in maze.iter_nodes() {
+        let mut targets: Vec<usize> = maze.get_links(node).iter().map(|n| n.pos()).collect();
+        targets.sort_unstable();
+        targets.dedup();
+        for target in targets {
+            let bi_link = maze.has_link(&maze[target], node);
+            if !bi_link || node.pos() < target {
+                edges.push((node.pos(), target, bi_link));
+            }
+        }
+    }
+    edges
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::to_dot;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn should_write_positions_attributes_and_edges() {
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n2, n3) = (maze[0], maze[1], maze[2], maze[3]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n3, true);
+        maze.link(&n2, &n3, false);
+        let distances = maze.distances(&n0);
+
+        let dot = to_dot(&maze, Some(&distances));
+        assert!(dot.starts_with("graph maze {"));
+        assert!(dot.contains("n0 [pos=\"0,72!\", row=0, col=0, weight=1, distance=0];"));
+        assert!(dot.contains("n3 [pos=\"72,0!\", row=1, col=1, weight=1, distance=2];"));
+        // node 2 can't be reached from node 0, so it has no distance
+        assert!(dot.contains("n2 [pos=\"0,0!\", row=1, col=0, weight=1];"));
+        assert!(dot.contains("n0 -- n1;"));
+        assert!(dot.contains("n1 -- n3;"));
+        assert!(dot.contains("n2 -- n3 [dir=\"forward\"];"));
+        assert_eq!(dot.matches("--").count(), 3);
+    }
+}
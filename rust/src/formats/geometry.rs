@@ -0,0 +1,132 @@
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::path::Path;
+
+/// A straight line segment, such as a wall of the maze.
+///
+/// All coordinates are in "cell units": the north-west corner of the maze is at (0, 0),
+/// x increases to the east and y increases to the south, so the maze occupies the rectangle
+/// from (0, 0) to (cols, rows). Renderers scale these units to pixels, points, etc.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Segment {
+    pub x1: f64,
+    pub y1: f64,
+    pub x2: f64,
+    pub y2: f64,
+}
+
+/// returns the walls of the `maze` as line segments, in cell units.
+/// Adjacent, collinear walls are merged into a single segment, so a maze with no links at all
+/// returns one segment per grid line.
+pub fn walls(maze: &GridMaze) -> Vec<Segment> {
+    let (rows, cols) = maze.dimensions();
+    let mut segments = vec![];
+
+    // horizontal walls, the top boundary plus the southern wall of every row
+    for y in 0..=rows {
+        let mut run_start: Option<usize> = None;
+        for x in 0..=cols {
+            let has_wall = x < cols && (y == 0 || has_south_wall(maze, y - 1, x));
+            match (has_wall, run_start) {
+                (true, None) => run_start = Some(x),
+                (false, Some(start)) => {
+                    segments.push(Segment { x1: start as f64, y1: y as f64, x2: x as f64, y2: y as f64 });
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    // vertical walls, the western boundary plus the eastern wall of every column
+    for x in 0..=cols {
+        let mut run_start: Option<usize> = None;
+        for y in 0..=rows {
+            let has_wall = y < rows && (x == 0 || has_east_wall(maze, y, x - 1));
+            match (has_wall, run_start) {
+                (true, None) => run_start = Some(y),
+                (false, Some(start)) => {
+                    segments.push(Segment { x1: x as f64, y1: start as f64, x2: x as f64, y2: y as f64 });
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    segments
+}
+
+/// returns true if a wall should be drawn on the southern side of the node at `row`, `col`.
+/// This mirrors the `Display` impl of `GridMaze`
+fn has_south_wall(maze: &GridMaze, row: usize, col: usize) -> bool {
+    match maze.get2d(row, col) {
+        Some(node) => match maze.south(&node) {
+            Some(south_node) => !maze.has_link(&node, &south_node),
+            None => true,
+        },
+        None => true,
+    }
+}
+
+/// returns true if a wall should be drawn on the eastern side of the node at `row`, `col`
+fn has_east_wall(maze: &GridMaze, row: usize, col: usize) -> bool {
+    match maze.get2d(row, col) {
+        Some(node) => match maze.east(&node) {
+            Some(east_node) => !maze.has_link(&node, &east_node),
+            None => true,
+        },
+        None => true,
+    }
+}
+
+/// returns the (x, y) coordinates, in cell units, of the north-west corner of `node`
+pub fn cell_origin(maze: &GridMaze, node: &GridNode) -> (f64, f64) {
+    let (_rows, cols) = maze.dimensions();
+    ((node.pos() % cols) as f64, (node.pos() / cols) as f64)
+}
+
+/// returns the (x, y) coordinates, in cell units, of the center of `node`
+pub fn cell_center(maze: &GridMaze, node: &GridNode) -> (f64, f64) {
+    let (x, y) = cell_origin(maze, node);
+    (x + 0.5, y + 0.5)
+}
+
+/// returns the center points of the nodes in `path`, in order, so the points can be drawn as a
+/// line from the start of the path to its goal.
+pub fn path_points(maze: &GridMaze, path: &Path) -> Vec<(f64, f64)> {
+    path.nodes()
+        .iter()
+        .map(|node| cell_center(maze, node))
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{walls, cell_center, Segment};
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn unlinked_maze_should_have_one_segment_per_grid_line() {
+        let maze = GridMaze::new(2, 3).unwrap();
+        // 3 horizontal lines and 4 vertical lines
+        assert_eq!(walls(&maze).len(), 7);
+    }
+
+    #[test]
+    fn linked_nodes_should_not_have_a_wall_between_them() {
+        let mut maze = GridMaze::new(1, 2).unwrap();
+        let (n0, n1) = (maze[0], maze[1]);
+        maze.link(&n0, &n1, true);
+        let segments = walls(&maze);
+        assert!(!segments.contains(&Segment { x1: 1.0, y1: 0.0, x2: 1.0, y2: 1.0 }));
+        assert!(segments.contains(&Segment { x1: 0.0, y1: 0.0, x2: 2.0, y2: 0.0 }));
+    }
+
+    #[test]
+    fn should_compute_cell_center() {
+        let maze = GridMaze::new(3, 3).unwrap();
+        assert_eq!(cell_center(&maze, &maze[5]), (2.5, 1.5));
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+use crate::formats::dot::edges;
+use crate::maze::grid_maze::GridMaze;
+use crate::solver::distances::Distances;
+
+/// returns the `maze` as a GraphML document.
+///
+/// The graph has `rows` and `cols` attributes, and every node has `row`, `col` and `weight`
+/// attributes. If `distances` is given, nodes that have a distance also get a `distance`
+/// attribute. Bi-directional links are written as undirected edges, one-way links are written
+/// with `directed="true"`.
+pub fn to_graphml(maze: &GridMaze, distances: Option<&Distances>) -> String {
+    let (rows, cols) = maze.dimensions();
+    let mut buf = String::new();
+    buf.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    buf.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    for (name, domain) in [("rows", "graph"), ("cols", "graph"), ("row", "node"), ("col", "node"),
+        ("weight", "node"), ("distance", "node")].iter() {
+        let _ = writeln!(
+            buf,
+            "  <key id=\"{n}\" for=\"{}\" attr.name=\"{n}\" attr.type=\"int\"/>",
+            domain, n = name
+        );
+    }
+    buf.push_str("  <graph id=\"maze\" edgedefault=\"undirected\">\n");
+    let _ = writeln!(buf, "    <data key=\"rows\">{}</data>", rows);
+    let _ = writeln!(buf, "    <data key=\"cols\">{}</data>", cols);
+
+    for node in maze.iter_nodes() {
+        let _ = write!(
+            buf,
+            "    <node id=\"n{}\"><data key=\"row\">{}</data><data key=\"col\">{}</data><data key=\"weight\">{}</data>",
+            node.pos(), node.pos() / cols, node.pos() % cols, node.weight()
+        );
+        if let Some(dist) = distances.and_then(|d| d.get(node)) {
+            let _ = write!(buf, "<data key=\"distance\">{}</data>", dist);
+        }
+        buf.push_str("</node>\n");
+    }
+
+    for (source, target, bi_link) in edges(maze) {
+        if bi_link {
+            let _ = writeln!(buf, "    <edge source=\"n{}\" target=\"n{}\"/>", source, target);
+        } else {
+            let _ = writeln!(buf, "    <edge source=\"n{}\" target=\"n{}\" directed=\"true\"/>", source, target);
+        }
+    }
+
+    buf.push_str("  </graph>\n</graphml>\n");
+    buf
+}
+
+/// GraphMlError is returned when a GraphML document can't be read into a GridMaze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GraphMlError {
+    /// the document is not well formed XML at byte `offset`
+    Malformed { offset: usize, reason: &'static str },
+    /// an `element` is missing a required `attribute`
+    MissingAttribute { element: String, attribute: &'static str },
+    /// the value of the data `key` of `node` is not an integer
+    InvalidNumber { node: String, key: String, value: String },
+    /// `node` does not have both a `row` and a `col` attribute
+    MissingPosition { node: String },
+    /// more than one node is at `row` and `col`
+    DuplicatePosition { row: usize, col: usize },
+    /// the nodes do not fill a `rows` by `cols` grid
+    NotGridShaped { rows: usize, cols: usize, nodes: usize },
+    /// an edge refers to a node that is not in the graph
+    UnknownNode(String),
+    /// an edge links two nodes that are not neighbors in the grid
+    NotNeighbors { source: String, target: String },
+}
+
+impl Display for GraphMlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphMlError::Malformed { offset, reason } => write!(f, "malformed XML at byte {}: {}", offset, reason),
+            GraphMlError::MissingAttribute { element, attribute } => {
+                write!(f, "<{}> is missing the {} attribute", element, attribute)
+            }
+            GraphMlError::InvalidNumber { node, key, value } => {
+                write!(f, "the {} of node {} is not an integer: {:?}", key, node, value)
+            }
+            GraphMlError::MissingPosition { node } => write!(f, "node {} needs a row and a col", node),
+            GraphMlError::DuplicatePosition { row, col } => {
+                write!(f, "there is more than one node at row {}, col {}", row, col)
+            }
+            GraphMlError::NotGridShaped { rows, cols, nodes } => {
+                write!(f, "{} nodes do not fill a {}x{} grid", nodes, rows, cols)
+            }
+            GraphMlError::UnknownNode(id) => write!(f, "an edge refers to the unknown node {}", id),
+            GraphMlError::NotNeighbors { source, target } => {
+                write!(f, "nodes {} and {} are not neighbors in the grid", source, target)
+            }
+        }
+    }
+}
+
+impl Error for GraphMlError {}
+
+/// a node read from a GraphML document, keyed by attribute name
+struct RawNode {
+    id: String,
+    data: HashMap<String, String>,
+}
+
+/// reads a GridMaze from a GraphML document.
+///
+/// The graph must be grid-shaped: every node needs integer `row` and `col` attributes (as written
+/// by `to_graphml`), the nodes must fill every position of the grid, and edges may only join
+/// neighboring nodes. An optional integer `weight` attribute sets the node's weight. Undirected
+/// edges become bi-directional links and directed edges become one-way links.
+///
+/// Attributes are matched by their `attr.name`, so documents written by other tools, which
+/// usually use ids like `d0` for their keys, can be read as well.
+pub fn from_graphml(text: &str) -> Result<GridMaze, GraphMlError> {
+    let mut key_names: HashMap<String, String> = HashMap::new();
+    let mut nodes: Vec<RawNode> = vec![];
+    let mut raw_edges: Vec<(String, String, Option<bool>)> = vec![];
+    let mut edges_directed = false;
+    // whether the reader is inside a <node> element, only the <data> of nodes is collected
+    let mut in_node = false;
+    // the key of the <data> element being read, if it belongs to a node
+    let mut data_key: Option<String> = None;
+
+    let mut reader = XmlReader { text, offset: 0 };
+    while let Some(token) = reader.next_token()? {
+        match token {
+            Token::Open { name, attrs, self_closing } => match name {
+                "key" => {
+                    let id = required(name, &attrs, "id")?;
+                    let attr_name = attrs.get("attr.name").cloned().unwrap_or_else(|| id.clone());
+                    key_names.insert(id, attr_name);
+                }
+                "graph" => edges_directed = attrs.get("edgedefault").is_some_and(|d| d == "directed"),
+                "node" => {
+                    nodes.push(RawNode { id: required(name, &attrs, "id")?, data: HashMap::new() });
+                    in_node = !self_closing;
+                }
+                "edge" => raw_edges.push((
+                    required(name, &attrs, "source")?,
+                    required(name, &attrs, "target")?,
+                    attrs.get("directed").map(|d| d == "true"),
+                )),
+                "data" if !self_closing && in_node => data_key = Some(required(name, &attrs, "key")?),
+                _ => {}
+            },
+            Token::Close { name } => match name {
+                "data" => data_key = None,
+                "node" => in_node = false,
+                _ => {}
+            },
+            Token::Text(value) => {
+                if let (Some(key), Some(node)) = (&data_key, nodes.last_mut()) {
+                    let name = key_names.get(key).unwrap_or(key).clone();
+                    node.data.entry(name).or_default().push_str(&value);
+                }
+            }
+        }
+    }
+
+    // work out the position of every node
+    let mut positions: HashMap<String, (usize, usize)> = HashMap::new();
+    let (mut rows, mut cols) = (0, 0);
+    for node in nodes.iter() {
+        let row = number(node, "row")?;
+        let col = number(node, "col")?;
+        match (row, col) {
+            (Some(row), Some(col)) if row >= 0 && col >= 0 => {
+                let (row, col) = (row as usize, col as usize);
+                rows = rows.max(row + 1);
+                cols = cols.max(col + 1);
+                positions.insert(node.id.clone(), (row, col));
+            }
+            _ => return Err(GraphMlError::MissingPosition { node: node.id.clone() }),
+        }
+    }
+    if positions.len() != nodes.len() || rows.checked_mul(cols) != Some(nodes.len()) {
+        return Err(GraphMlError::NotGridShaped { rows, cols, nodes: nodes.len() });
+    }
+
+    // a graph without any nodes doesn't make a maze either
+    let mut maze = GridMaze::new(rows, cols)
+        .map_err(|_| GraphMlError::NotGridShaped { rows, cols, nodes: nodes.len() })?;
+    let mut filled = vec![false; nodes.len()];
+    for node in nodes.iter() {
+        let (row, col) = positions[&node.id];
+        let pos = GridMaze::idx_1d(row, col, cols);
+        if filled[pos] {
+            return Err(GraphMlError::DuplicatePosition { row, col });
+        }
+        filled[pos] = true;
+        if let Some(weight) = number(node, "weight")? {
+            if let Some(grid_node) = maze.iter_mut_nodes().nth(pos) {
+                grid_node.set_weight(weight);
+            }
+        }
+    }
+
+    for (source, target, directed) in raw_edges {
+        let &(s_row, s_col) = positions.get(&source).ok_or_else(|| GraphMlError::UnknownNode(source.clone()))?;
+        let &(t_row, t_col) = positions.get(&target).ok_or_else(|| GraphMlError::UnknownNode(target.clone()))?;
+        if s_row.abs_diff(t_row) + s_col.abs_diff(t_col) != 1 {
+            return Err(GraphMlError::NotNeighbors { source, target });
+        }
+        let source_node = maze[GridMaze::idx_1d(s_row, s_col, cols)];
+        let target_node = maze[GridMaze::idx_1d(t_row, t_col, cols)];
+        maze.link(&source_node, &target_node, !directed.unwrap_or(edges_directed));
+    }
+
+    Ok(maze)
+}
+
+/// returns the value of the `attribute` of `element`, or an error if it is missing
+fn required(element: &str, attrs: &HashMap<String, String>, attribute: &'static str) -> Result<String, GraphMlError> {
+    attrs.get(attribute)
+        .cloned()
+        .ok_or_else(|| GraphMlError::MissingAttribute { element: element.to_string(), attribute })
+}
+
+/// returns the data `key` of `node` as an integer, or `None` if the node doesn't have that key
+fn number(node: &RawNode, key: &str) -> Result<Option<isize>, GraphMlError> {
+    match node.data.get(key) {
+        Some(value) => value.trim().parse().map(Some).map_err(|_| GraphMlError::InvalidNumber {
+            node: node.id.clone(),
+            key: key.to_string(),
+            value: value.clone(),
+        }),
+        None => Ok(None),
+    }
+}
+
+/// the parts of an XML document that are needed to read GraphML
+enum Token<'a> {
+    Open { name: &'a str, attrs: HashMap<String, String>, self_closing: bool },
+    Close { name: &'a str },
+    Text(String),
+}
+
+/// a minimal XML reader that splits a document into tags and text. Comments, processing
+/// instructions and declarations are skipped. It only understands the predefined entities.
+struct XmlReader<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> XmlReader<'a> {
+
+    /// returns the next tag or piece of text, or `None` at the end of the document
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, GraphMlError> {
+        loop {
+            let rest = &self.text[self.offset..];
+            if rest.is_empty() {
+                return Ok(None);
+            }
+
+            if !rest.starts_with('<') {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let start = self.offset;
+                self.offset += end;
+                return Ok(Some(Token::Text(unescape(&rest[..end], start)?)));
+            }
+
+            // skip comments, processing instructions and declarations
+            let skipped = [("<!--", "-->"), ("<?", "?>"), ("<!", ">")]
+                .iter()
+                .find(|(open, _close)| rest.starts_with(open));
+            if let Some((_open, close)) = skipped {
+                let end = rest.find(close).ok_or(self.malformed("unterminated markup"))?;
+                self.offset += end + close.len();
+                continue;
+            }
+
+            let end = rest.find('>').ok_or(self.malformed("unterminated tag"))?;
+            let tag = &rest[1..end];
+            let start = self.offset;
+            self.offset += end + 1;
+
+            if let Some(name) = tag.strip_prefix('/') {
+                return Ok(Some(Token::Close { name: name.trim() }));
+            }
+            let (tag, self_closing) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            let name = &tag[..name_end];
+            if name.is_empty() {
+                return Err(GraphMlError::Malformed { offset: start, reason: "missing tag name" });
+            }
+            let attrs = parse_attributes(&tag[name_end..], start + 1 + name_end)?;
+            return Ok(Some(Token::Open { name, attrs, self_closing }));
+        }
+    }
+
+    fn malformed(&self, reason: &'static str) -> GraphMlError {
+        GraphMlError::Malformed { offset: self.offset, reason }
+    }
+}
+
+/// parses the `name="value"` attributes of a tag. `offset` is the position of `text` in the document
+fn parse_attributes(text: &str, offset: usize) -> Result<HashMap<String, String>, GraphMlError> {
+    let mut attrs = HashMap::new();
+    let mut rest = text.trim_start();
+    while !rest.is_empty() {
+        let at = offset + text.len() - rest.len();
+        let eq = rest.find('=').ok_or(GraphMlError::Malformed { offset: at, reason: "attribute without a value" })?;
+        let name = rest[..eq].trim();
+        let value_part = rest[eq + 1..].trim_start();
+        let quote = value_part.chars().next()
+            .filter(|c| *c == '"' || *c == '\'')
+            .ok_or(GraphMlError::Malformed { offset: at, reason: "unquoted attribute value" })?;
+        let close = value_part[1..].find(quote)
+            .ok_or(GraphMlError::Malformed { offset: at, reason: "unterminated attribute value" })?;
+        attrs.insert(name.to_string(), unescape(&value_part[1..close + 1], at)?);
+        rest = value_part[close + 2..].trim_start();
+    }
+    Ok(attrs)
+}
+
+/// replaces the predefined XML entities, and character references, in `text`
+fn unescape(text: &str, offset: usize) -> Result<String, GraphMlError> {
+    let mut buf = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        buf.push_str(&rest[..amp]);
+        let semi = rest[amp..].find(';')
+            .ok_or(GraphMlError::Malformed { offset, reason: "unterminated entity" })?;
+        let entity = &rest[amp + 1..amp + semi];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity.strip_prefix('#').and_then(|dec| dec.parse().ok()).and_then(char::from_u32),
+            },
+        };
+        buf.push(c.ok_or(GraphMlError::Malformed { offset, reason: "unknown entity" })?);
+        rest = &rest[amp + semi + 1..];
+    }
+    buf.push_str(rest);
+    Ok(buf)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{to_graphml, from_graphml, GraphMlError};
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn every_generator_should_round_trip() {
+        for algo in Algorithm::ALL.iter() {
+            let maze = algo.generate_with_rng(6, 8, StdRng::seed_from_u64(3)).unwrap();
+            let distances = maze.distances(&maze[0]);
+            let graphml = to_graphml(&maze, Some(&distances));
+            let loaded = from_graphml(&graphml).unwrap();
+            assert_eq!(loaded.to_string(), maze.to_string(), "{} did not round trip", algo);
+            for node in maze.iter_nodes() {
+                assert_eq!(loaded[node.pos()].weight(), node.weight());
+            }
+        }
+    }
+
+    #[test]
+    fn should_read_documents_from_other_tools() {
+        let graphml = r#"<?xml version="1.0"?>
+            <!-- written by hand -->
+            <graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+              <key id="d0" for="node" attr.name="row" attr.type="int"/>
+              <key id="d1" for="node" attr.name="col" attr.type="int"/>
+              <graph edgedefault="directed">
+                <node id="a"><data key="d0">0</data><data key="d1">0</data></node>
+                <node id="b"><data key="d0">0</data><data key="d1">1</data></node>
+                <edge source="a" target="b"/>
+              </graph>
+            </graphml>"#;
+        let maze = from_graphml(graphml).unwrap();
+        assert_eq!(maze.dimensions(), (1, 2));
+        assert!(maze.has_link(&maze[0], &maze[1]));
+        assert!(!maze.has_link(&maze[1], &maze[0]));
+    }
+
+    #[test]
+    fn should_only_read_data_of_nodes() {
+        let graphml = r#"<graphml>
+              <key id="row" for="node" attr.name="row"/>
+              <key id="col" for="node" attr.name="col"/>
+              <key id="w" for="all" attr.name="weight"/>
+              <graph edgedefault="undirected">
+                <node id="a"><data key="row">0</data><data key="col">0</data></node>
+                <node id="b"><data key="row">0</data><data key="col">1</data><data key="w">1</data></node>
+                <edge source="a" target="b"><data key="w">5</data></edge>
+                <data key="w">oops</data>
+              </graph>
+            </graphml>"#;
+        let maze = from_graphml(graphml).unwrap();
+        assert_eq!(maze[0].weight(), 1);
+        assert_eq!(maze[1].weight(), 1);
+        assert!(maze.has_link(&maze[0], &maze[1]));
+    }
+
+    #[test]
+    fn should_keep_one_way_links() {
+        let mut maze = GridMaze::new(1, 3).unwrap();
+        let (n0, n1, n2) = (maze[0], maze[1], maze[2]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n2, &n1, false);
+        let graphml = to_graphml(&maze, None);
+        assert!(graphml.contains("<edge source=\"n2\" target=\"n1\" directed=\"true\"/>"));
+
+        let loaded = from_graphml(&graphml).unwrap();
+        assert!(loaded.has_link(&n1, &n0));
+        assert!(loaded.has_link(&n2, &n1));
+        assert!(!loaded.has_link(&n1, &n2));
+    }
+
+    #[test]
+    fn should_reject_graphs_that_are_not_grids() {
+        let node = |id: &str, row: usize, col: usize| format!(
+            "<node id=\"{}\"><data key=\"row\">{}</data><data key=\"col\">{}</data></node>", id, row, col
+        );
+        let doc = |body: String| format!(
+            "<graphml><key id=\"row\" attr.name=\"row\"/><key id=\"col\" attr.name=\"col\"/><graph>{}</graph></graphml>", body
+        );
+
+        let missing_corner = doc(node("a", 0, 0) + &node("b", 0, 1) + &node("c", 1, 0));
+        assert_eq!(from_graphml(&missing_corner).unwrap_err(), GraphMlError::NotGridShaped { rows: 2, cols: 2, nodes: 3 });
+
+        let diagonal = doc(node("a", 0, 0) + &node("b", 0, 1) + &node("c", 1, 0) + &node("d", 1, 1)
+            + "<edge source=\"a\" target=\"d\"/>");
+        assert_eq!(
+            from_graphml(&diagonal).unwrap_err(),
+            GraphMlError::NotNeighbors { source: "a".to_string(), target: "d".to_string() }
+        );
+
+        let unplaced = doc("<node id=\"a\"/>".to_string());
+        assert_eq!(from_graphml(&unplaced).unwrap_err(), GraphMlError::MissingPosition { node: "a".to_string() });
+    }
+}
@@ -0,0 +1,268 @@
+use std::fmt::Write;
+use crate::formats::geometry::{walls, cell_origin, path_points};
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::dijkstras::shortest_path;
+
+/// size of a cell of the maze, in pixels
+const CELL_SIZE: f64 = 24.0;
+
+/// size of the margin around the maze, in pixels
+const MARGIN: f64 = 12.0;
+
+// bit flags used to encode the passages out of a node, for the javascript "play mode"
+const NORTH: u8 = 1;
+const EAST: u8 = 2;
+const SOUTH: u8 = 4;
+const WEST: u8 = 8;
+
+/// the script that implements the solution/heatmap toggles and the arrow-key play mode
+const SCRIPT: &str = r#"
+(function () {
+  const DIRS = {
+    ArrowUp: [1, -1, 0], ArrowRight: [2, 0, 1], ArrowDown: [4, 1, 0], ArrowLeft: [8, 0, -1],
+  };
+  const player = document.getElementById("player");
+  const trail = document.getElementById("trail");
+  const status = document.getElementById("status");
+  let pos = MAZE.start;
+  let moves = 0;
+  let points = [];
+
+  function center(p) {
+    const row = Math.floor(p / MAZE.cols), col = p % MAZE.cols;
+    return [MAZE.margin + (col + 0.5) * MAZE.cell, MAZE.margin + (row + 0.5) * MAZE.cell];
+  }
+
+  function draw() {
+    const [x, y] = center(pos);
+    player.setAttribute("cx", x);
+    player.setAttribute("cy", y);
+    points.push(x + "," + y);
+    trail.setAttribute("points", points.join(" "));
+    status.textContent = pos === MAZE.goal
+      ? "Solved in " + moves + " moves! The shortest path takes " + MAZE.shortest + " moves."
+      : "Moves: " + moves + ". Use the arrow keys to walk to the red square.";
+  }
+
+  function toggle(checkbox, id) {
+    document.getElementById(checkbox).addEventListener("change", function (e) {
+      document.getElementById(id).style.display = e.target.checked ? "inline" : "none";
+    });
+  }
+  toggle("show-solution", "solution");
+  toggle("show-heatmap", "heatmap");
+
+  document.getElementById("restart").addEventListener("click", function () {
+    pos = MAZE.start;
+    moves = 0;
+    points = [];
+    draw();
+  });
+
+  document.addEventListener("keydown", function (e) {
+    const dir = DIRS[e.key];
+    if (!dir) return;
+    e.preventDefault();
+    if (pos === MAZE.goal || (MAZE.links[pos] & dir[0]) === 0) return;
+    pos += dir[1] * MAZE.cols + dir[2];
+    moves += 1;
+    draw();
+  });
+
+  draw();
+})();
+"#;
+
+/// returns a self-contained HTML page that displays the `maze` as an SVG image.
+///
+/// The page contains toggles that show the shortest path (as found by Dijkstra's algorithm) and
+/// a heatmap of the distance of every cell from the start. It also has a "play mode" where the
+/// arrow keys walk from the start cell to the goal cell.
+///
+/// The page does not reference any external assets, so it can be saved to a file and
+/// opened, or emailed, as is.
+pub fn to_html(maze: &GridMaze, title: &str) -> String {
+    let (rows, cols) = maze.dimensions();
+    let title = escape(title);
+    let width = cols as f64 * CELL_SIZE + 2.0 * MARGIN;
+    let height = rows as f64 * CELL_SIZE + 2.0 * MARGIN;
+    let scale = |units: f64| MARGIN + units * CELL_SIZE;
+
+    let mut buf = String::new();
+    let _ = writeln!(buf, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">");
+    let _ = writeln!(buf, "<title>{}</title>", title);
+    buf.push_str("<style>\n\
+        body { font-family: sans-serif; margin: 2em; }\n\
+        .controls { margin: 1em 0; }\n\
+        .controls label { margin-right: 1.5em; }\n\
+        #walls { stroke: #222; stroke-width: 2; stroke-linecap: square; }\n\
+        #solution polyline { fill: none; stroke: #1a9e3a; stroke-width: 4; stroke-linejoin: round; }\n\
+        #trail { fill: none; stroke: #3377ff; stroke-width: 2; stroke-opacity: 0.6; }\n\
+        </style>\n</head>\n<body>\n");
+    let _ = writeln!(buf, "<h1>{}</h1>", title);
+    buf.push_str("<div class=\"controls\">\n\
+        <label><input type=\"checkbox\" id=\"show-solution\"> Show solution</label>\n\
+        <label><input type=\"checkbox\" id=\"show-heatmap\"> Show distance heatmap</label>\n\
+        <button id=\"restart\">Restart</button>\n\
+        </div>\n\
+        <p id=\"status\"></p>\n");
+
+    let _ = writeln!(
+        buf,
+        "<svg width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">",
+        w = width,
+        h = height
+    );
+
+    let start = maze.start();
+    let goal = maze.goal();
+    let distances = maze.distances(&start);
+
+    // the distance heatmap, hidden by default
+    let max_distance = maze.iter_nodes()
+        .filter_map(|node| distances.get(node))
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(1);
+    buf.push_str("<g id=\"heatmap\" style=\"display:none\">\n");
+    for node in maze.iter_nodes() {
+        if let Some(dist) = distances.get(node) {
+            let (x, y) = cell_origin(maze, node);
+            // hue runs from blue, at the start, to red at the farthest cell
+            let hue = 240.0 - 240.0 * (*dist as f64 / max_distance as f64);
+            let _ = writeln!(
+                buf,
+                "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"hsl({:.0}, 85%, 65%)\"><title>{}</title></rect>",
+                scale(x), scale(y), hue, dist, s = CELL_SIZE
+            );
+        }
+    }
+    buf.push_str("</g>\n");
+
+    // start and goal markers
+    for (node, color) in [(start, "#3377ff"), (goal, "#e0393e")].iter() {
+        let (x, y) = cell_origin(maze, node);
+        let _ = writeln!(
+            buf,
+            "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"{}\" fill-opacity=\"0.35\"/>",
+            scale(x) + 3.0, scale(y) + 3.0, color, s = CELL_SIZE - 6.0
+        );
+    }
+
+    // the shortest path, hidden by default. It is empty if the goal can't be reached
+    let points: Vec<String> = match shortest_path(maze, start, goal) {
+        Some(path) => path_points(maze, &path)
+            .iter()
+            .map(|(x, y)| format!("{},{}", scale(*x), scale(*y)))
+            .collect(),
+        None => vec![],
+    };
+    let _ = writeln!(
+        buf,
+        "<g id=\"solution\" style=\"display:none\"><polyline points=\"{}\"/></g>",
+        points.join(" ")
+    );
+
+    // the "play mode" trail and player
+    buf.push_str("<polyline id=\"trail\" points=\"\"/>\n");
+    let _ = writeln!(buf, "<circle id=\"player\" r=\"{}\" fill=\"#3377ff\"/>", CELL_SIZE / 3.0);
+
+    let links: Vec<String> = maze.iter_nodes()
+        .map(|node| passages(maze, node).to_string())
+        .collect();
+    let shortest = points.len().saturating_sub(1);
+
+    draw_walls(&mut buf, maze, &scale);
+    buf.push_str("</svg>\n");
+
+    // the maze data used by the play mode
+    let _ = writeln!(
+        buf,
+        "<script>\nconst MAZE = {{ rows: {}, cols: {}, cell: {}, margin: {}, start: {}, goal: {}, shortest: {}, links: [{}] }};\n</script>",
+        rows, cols, CELL_SIZE, MARGIN, start.pos(), goal.pos(), shortest, links.join(",")
+    );
+    let _ = writeln!(buf, "<script>{}</script>", SCRIPT);
+
+    buf.push_str("</body>\n</html>\n");
+    buf
+}
+
+/// writes the walls of the `maze` into `buf` as a single SVG path
+fn draw_walls<F: Fn(f64) -> f64>(buf: &mut String, maze: &GridMaze, scale: &F) {
+    buf.push_str("<path id=\"walls\" d=\"");
+    for wall in walls(maze) {
+        let _ = write!(
+            buf,
+            "M{} {}L{} {}",
+            scale(wall.x1), scale(wall.y1), scale(wall.x2), scale(wall.y2)
+        );
+    }
+    buf.push_str("\"/>\n");
+}
+
+/// returns the passages out of `node` as a combination of the NORTH, EAST, SOUTH and WEST flags
+fn passages(maze: &GridMaze, node: &GridNode) -> u8 {
+    let directions = [
+        (maze.north(node), NORTH),
+        (maze.east(node), EAST),
+        (maze.south(node), SOUTH),
+        (maze.west(node), WEST),
+    ];
+    directions.iter()
+        .filter(|(neighbor, _flag)| neighbor.is_some_and(|n| maze.has_link(node, &n)))
+        .fold(0, |bits, (_neighbor, flag)| bits | flag)
+}
+
+/// escapes the characters of `text` that have special meaning in HTML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{to_html, passages, EAST, SOUTH};
+    use crate::generator::recursive_backtracker;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn html_should_not_reference_external_assets() {
+        let maze = recursive_backtracker::generate(5, 5).unwrap();
+        let html = to_html(&maze, "test maze");
+        assert!(!html.contains("http://"));
+        assert!(!html.contains("https://"));
+        assert!(!html.contains(" src="));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("id=\"solution\""));
+        assert!(html.contains("id=\"heatmap\""));
+    }
+
+    #[test]
+    fn should_escape_title() {
+        let maze = GridMaze::new(2, 2).unwrap();
+        let html = to_html(&maze, "<b>mazes & more</b>");
+        assert!(html.contains("&lt;b&gt;mazes &amp; more&lt;/b&gt;"));
+    }
+
+    #[test]
+    fn should_encode_passages_as_bit_flags() {
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n2) = (maze[0], maze[1], maze[2]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n0, &n2, true);
+        assert_eq!(passages(&maze, &n0), EAST | SOUTH);
+    }
+}
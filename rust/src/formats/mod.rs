@@ -0,0 +1,29 @@
+//! Modules for reading and writing mazes in other formats
+
+/// vector geometry (walls and cell centers) of a maze, shared by the vector based formats
+pub mod geometry;
+
+/// exports a maze as a self-contained, interactive HTML page
+pub mod html;
+
+/// writes printable PDF documents of mazes and their solutions
+pub mod pdf;
+
+/// converts mazes to and from "roguelike" tile maps, where walls are full tiles
+pub mod tiles;
+
+/// encodes mazes in a compact binary format, using two bits per cell
+pub mod binary;
+
+/// parses mazes drawn as ASCII text, in the format written by `GridMaze`'s `Display` impl
+pub mod ascii;
+
+/// exports mazes as GraphViz DOT graphs
+pub mod dot;
+
+/// exports mazes to, and imports them from, GraphML documents
+pub mod graphml;
+
+/// encodes mazes as short, optionally compressed, URL-safe strings that can be shared in links and
+/// chat messages
+pub mod url;
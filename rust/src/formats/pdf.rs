@@ -0,0 +1,315 @@
+use std::fmt::Write;
+use crate::formats::geometry::{walls, path_points};
+use crate::maze::grid_maze::GridMaze;
+use crate::solver::dijkstras::shortest_path;
+
+/// size of the title text, in points
+const TITLE_SIZE: f64 = 18.0;
+
+/// size of the caption text beneath each maze, in points
+const CAPTION_SIZE: f64 = 10.0;
+
+/// space between the mazes on a page, in points
+const GUTTER: f64 = 18.0;
+
+/// The size of a PDF page, all sizes are in points (1/72 of an inch)
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PageSize {
+    /// 8.5 x 11 inches
+    Letter,
+    /// 210 x 297 mm
+    A4,
+    /// a custom page size, in points
+    Custom { width: f64, height: f64 },
+}
+
+impl PageSize {
+    /// returns the (width, height) of the page in points
+    pub fn dimensions(&self) -> (f64, f64) {
+        match *self {
+            PageSize::Letter => (612.0, 792.0),
+            PageSize::A4 => (595.28, 841.89),
+            PageSize::Custom { width, height } => (width, height),
+        }
+    }
+}
+
+/// Options that control how mazes are laid out on the pages of a PDF
+#[derive(Debug, Clone)]
+pub struct PdfOptions {
+    pub page_size: PageSize,
+    /// the margin around all four sides of a page, in points
+    pub margin: f64,
+    /// the title printed at the top of each page, if any
+    pub title: Option<String>,
+    /// the number of mazes printed side by side on a page
+    pub columns: usize,
+    /// the number of rows of mazes printed on a page
+    pub rows: usize,
+    /// if true, every page of puzzles is followed by a matching page of solutions
+    pub solutions: bool,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            page_size: PageSize::Letter,
+            margin: 36.0,
+            title: None,
+            columns: 1,
+            rows: 1,
+            solutions: true,
+        }
+    }
+}
+
+/// PdfMaze is a maze to be printed, along with the (optional) seed that generated it.
+/// The seed is printed as a caption beneath the maze
+#[derive(Debug, Copy, Clone)]
+pub struct PdfMaze<'a> {
+    pub maze: &'a GridMaze,
+    pub seed: Option<u64>,
+}
+
+/// returns a PDF document containing the `mazes`, laid out according to `options`.
+///
+/// The solution drawn on a solution page is the shortest path from the start to the goal of a
+/// maze, as found by `shortest_path`. Walls and paths are drawn with the same geometry used by the
+/// other vector formats, see `formats::geometry`.
+pub fn to_pdf(mazes: &[PdfMaze], options: &PdfOptions) -> Vec<u8> {
+    let per_page = (options.columns * options.rows).max(1);
+
+    // each page is a content stream, puzzles pages are followed by their solution page
+    let mut pages = vec![];
+    for (page_num, chunk) in mazes.chunks(per_page).enumerate() {
+        pages.push(page_content(chunk, page_num * per_page, options, false));
+        if options.solutions {
+            pages.push(page_content(chunk, page_num * per_page, options, true));
+        }
+    }
+    if pages.is_empty() {
+        pages.push(page_content(&[], 0, options, false));
+    }
+
+    write_document(&pages, options.page_size)
+}
+
+/// returns the content stream of a single page, containing `mazes`. `first_index` is the index of
+/// the first maze on the page, used to number the mazes. If `solution` is true, the solution of
+/// each maze is also drawn
+fn page_content(mazes: &[PdfMaze], first_index: usize, options: &PdfOptions, solution: bool) -> String {
+    let (page_width, page_height) = options.page_size.dimensions();
+    let columns = options.columns.max(1);
+    let rows = options.rows.max(1);
+    let mut content = String::new();
+
+    // the top of the area the mazes are laid out in, measured down from the top of the page
+    let mut top = options.margin;
+    if let Some(title) = &options.title {
+        let title = if solution { format!("{} - solutions", title) } else { title.clone() };
+        let _ = writeln!(
+            content,
+            "BT /F1 {} Tf {:.2} {:.2} Td ({}) Tj ET",
+            TITLE_SIZE,
+            options.margin,
+            page_height - options.margin - TITLE_SIZE,
+            escape(&title)
+        );
+        top += TITLE_SIZE * 2.0;
+    }
+
+    // the size of the "slot" each maze, and its caption, is drawn into
+    let area_width = page_width - 2.0 * options.margin;
+    let area_height = page_height - top - options.margin;
+    let slot_width = (area_width - GUTTER * (columns - 1) as f64) / columns as f64;
+    let slot_height = (area_height - GUTTER * (rows - 1) as f64) / rows as f64;
+
+    for (i, pdf_maze) in mazes.iter().enumerate() {
+        let slot_x = options.margin + (i % columns) as f64 * (slot_width + GUTTER);
+        let slot_y = top + (i / columns) as f64 * (slot_height + GUTTER);
+        let (maze_rows, maze_cols) = pdf_maze.maze.dimensions();
+
+        // scale the maze so that it fits in the slot, leaving room for the caption
+        let cell = (slot_width / maze_cols.max(1) as f64)
+            .min((slot_height - CAPTION_SIZE * 2.0) / maze_rows.max(1) as f64)
+            .max(0.0);
+        let maze_x = slot_x + (slot_width - cell * maze_cols as f64) / 2.0;
+        let maze_y = slot_y;
+        // converts cell units to PDF coordinates, which have their origin at the bottom left
+        let to_x = |x: f64| maze_x + x * cell;
+        let to_y = |y: f64| page_height - (maze_y + y * cell);
+
+        let _ = writeln!(content, "q {:.2} w 0 0 0 RG 1 J", (cell / 10.0).clamp(0.5, 2.0));
+        for wall in walls(pdf_maze.maze) {
+            let _ = writeln!(
+                content,
+                "{:.2} {:.2} m {:.2} {:.2} l S",
+                to_x(wall.x1), to_y(wall.y1), to_x(wall.x2), to_y(wall.y2)
+            );
+        }
+        let _ = writeln!(content, "Q");
+
+        if solution {
+            draw_solution(&mut content, pdf_maze.maze, cell, &to_x, &to_y);
+        }
+
+        let caption = match pdf_maze.seed {
+            Some(seed) => format!("#{}  {}x{}  seed: {}", first_index + i + 1, maze_rows, maze_cols, seed),
+            None => format!("#{}  {}x{}", first_index + i + 1, maze_rows, maze_cols),
+        };
+        let _ = writeln!(
+            content,
+            "BT /F1 {} Tf {:.2} {:.2} Td ({}) Tj ET",
+            CAPTION_SIZE,
+            maze_x,
+            to_y(maze_rows as f64) - CAPTION_SIZE * 1.5,
+            escape(&caption)
+        );
+    }
+
+    content
+}
+
+/// draws the shortest path from the start to the goal of the `maze`.
+/// Nothing is drawn if there is no path between them
+fn draw_solution<X, Y>(content: &mut String, maze: &GridMaze, cell: f64, to_x: &X, to_y: &Y)
+where
+    X: Fn(f64) -> f64,
+    Y: Fn(f64) -> f64,
+{
+    let start = maze.start();
+    let goal = maze.goal();
+    let path = match shortest_path(maze, start, goal) {
+        Some(path) => path,
+        None => return,
+    };
+
+    let points = path_points(maze, &path);
+    let _ = writeln!(content, "q {:.2} w 0.1 0.6 0.23 RG 1 J 1 j", (cell / 4.0).max(0.5));
+    for (i, (x, y)) in points.iter().enumerate() {
+        let op = if i == 0 { "m" } else { "l" };
+        let _ = writeln!(content, "{:.2} {:.2} {}", to_x(*x), to_y(*y), op);
+    }
+    if points.len() == 1 {
+        // a path with a single point, draw it as a dot
+        let (x, y) = points[0];
+        let _ = writeln!(content, "{:.2} {:.2} l", to_x(x), to_y(y));
+    }
+    let _ = writeln!(content, "S Q");
+}
+
+/// writes the PDF document structure (catalog, page tree, font, pages and cross-reference table)
+/// around the content streams in `pages`
+fn write_document(pages: &[String], page_size: PageSize) -> Vec<u8> {
+    let (width, height) = page_size.dimensions();
+
+    // object 1 is the catalog, 2 is the page tree, 3 is the font, then each page is
+    // followed by its content stream
+    let page_ids: Vec<usize> = (0..pages.len()).map(|i| 4 + i * 2).collect();
+    let mut objects: Vec<String> = vec![
+        String::from("<< /Type /Catalog /Pages 2 0 R >>"),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            page_ids.iter().map(|id| format!("{} 0 R", id)).collect::<Vec<String>>().join(" "),
+            pages.len()
+        ),
+        String::from("<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>"),
+    ];
+    for (page_id, content) in page_ids.iter().zip(pages) {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.2} {:.2}] /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+            width,
+            height,
+            page_id + 1
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", content.len(), content));
+    }
+
+    let mut pdf: Vec<u8> = Vec::new();
+    pdf.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+    let mut offsets = vec![];
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", i + 1, object).as_bytes());
+    }
+
+    let xref_offset = pdf.len();
+    let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+    for offset in offsets {
+        let _ = writeln!(xref, "{:010} 00000 n ", offset);
+    }
+    let _ = write!(
+        xref,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        xref_offset
+    );
+    pdf.extend_from_slice(xref.as_bytes());
+    pdf
+}
+
+/// escapes `text` for use in a PDF string literal. Characters outside of printable ASCII are
+/// replaced by a '?', as the standard Helvetica font only covers ASCII reliably
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{to_pdf, escape, PdfMaze, PdfOptions};
+    use crate::generator::recursive_backtracker;
+
+    /// returns the number of times `needle` occurs in `pdf`
+    fn count(pdf: &[u8], needle: &str) -> usize {
+        pdf.windows(needle.len()).filter(|w| *w == needle.as_bytes()).count()
+    }
+
+    #[test]
+    fn should_write_puzzle_and_solution_pages() {
+        let maze1 = recursive_backtracker::generate(5, 5).unwrap();
+        let maze2 = recursive_backtracker::generate(4, 6).unwrap();
+        let mazes = [PdfMaze { maze: &maze1, seed: Some(42) }, PdfMaze { maze: &maze2, seed: None }];
+        let options = PdfOptions { title: Some(String::from("Mazes")), ..PdfOptions::default() };
+        let pdf = to_pdf(&mazes, &options);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        // one maze per page, each followed by a solution page
+        assert_eq!(count(&pdf, "/Type /Page "), 4);
+        assert_eq!(count(&pdf, "seed: 42"), 2);
+        assert_eq!(count(&pdf, "Mazes - solutions"), 2);
+    }
+
+    #[test]
+    fn xref_offsets_should_point_at_objects() {
+        let maze = recursive_backtracker::generate(3, 3).unwrap();
+        let options = PdfOptions { columns: 2, rows: 2, ..PdfOptions::default() };
+        let pdf = to_pdf(&[PdfMaze { maze: &maze, seed: None }], &options);
+        let text = String::from_utf8_lossy(&pdf).into_owned();
+
+        let xref = &text[text.find("xref\n").unwrap()..];
+        for (i, line) in xref.lines().skip(3).take_while(|l| l.ends_with(" n ")).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            let expected = format!("{} 0 obj", i + 1);
+            assert_eq!(&pdf[offset..offset + expected.len()], expected.as_bytes());
+        }
+    }
+
+    #[test]
+    fn should_escape_pdf_strings() {
+        assert_eq!(escape("a (b) \\ é"), "a \\(b\\) \\\\ ?");
+    }
+}
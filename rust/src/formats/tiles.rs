@@ -0,0 +1,410 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+use crate::maze::grid_maze::GridMaze;
+
+/// the global tile ids used for walls and floors in the Tiled (TMX and JSON) exports
+const WALL_GID: u32 = 1;
+const FLOOR_GID: u32 = 2;
+
+/// A single tile of a `TileMap`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Tile {
+    Wall,
+    Floor,
+}
+
+impl Tile {
+    /// returns the character used for this tile in the plain text format
+    pub fn to_char(self) -> char {
+        match self {
+            Tile::Wall => '#',
+            Tile::Floor => '.',
+        }
+    }
+}
+
+/// TileError is returned when a tile map can't be parsed, or can't be converted into a maze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TileError {
+    /// a tile map must have an odd number of rows and columns, and at least 3 of each
+    InvalidDimensions { rows: usize, cols: usize },
+    /// a row of the tile map has a different length than the first row. `row` is the index of its
+    /// line in the input, blank lines included
+    RaggedRow { row: usize, len: usize, expected: usize },
+    /// a character, or CSV value, that is not a valid tile. `row` is the index of its line in the
+    /// input, blank lines included
+    InvalidTile { row: usize, col: usize, value: String },
+    /// the tile at `row`, `col` must be `expected` for the tile map to be a maze
+    UnexpectedTile { row: usize, col: usize, expected: Tile },
+}
+
+impl Display for TileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TileError::InvalidDimensions { rows, cols } => write!(
+                f,
+                "a {}x{} tile map is not a maze, its dimensions must be odd and at least 3x3",
+                rows, cols
+            ),
+            TileError::RaggedRow { row, len, expected } => write!(
+                f,
+                "row {} has {} tiles, expected {}",
+                row, len, expected
+            ),
+            TileError::InvalidTile { row, col, value } => write!(
+                f,
+                "invalid tile {:?} at row {}, column {}",
+                value, row, col
+            ),
+            TileError::UnexpectedTile { row, col, expected } => write!(
+                f,
+                "tile at row {}, column {} should be a {:?}",
+                row, col, expected
+            ),
+        }
+    }
+}
+
+impl Error for TileError {}
+
+/// TileMap is a "roguelike" representation of a maze, where walls are full tiles instead of the
+/// edges between cells. A maze with `rows` x `cols` cells becomes a (2·rows+1) x (2·cols+1) map:
+///
+/// - the cell at (row, col) is the floor tile at (2·row+1, 2·col+1)
+/// - the tile between two neighboring cells is a floor if there is a passage between them,
+///   else it is a wall
+/// - the outer border, and the tiles at the corners of the cells, are always walls
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TileMap {
+    rows: usize,
+    cols: usize,
+    // tiles in row order
+    tiles: Vec<Tile>,
+}
+
+impl TileMap {
+    /// converts the `maze` into a tile map. As with the `Display` impl of `GridMaze`, the tile
+    /// between two cells is a floor if there is a link from the west (or north) cell to its
+    /// east (or south) neighbor
+    pub fn from_maze(maze: &GridMaze) -> Self {
+        let (maze_rows, maze_cols) = maze.dimensions();
+        let rows = 2 * maze_rows + 1;
+        let cols = 2 * maze_cols + 1;
+        let mut tiles = vec![Tile::Wall; rows * cols];
+
+        for node in maze.iter_nodes() {
+            let row = 2 * (node.pos() / maze_cols) + 1;
+            let col = 2 * (node.pos() % maze_cols) + 1;
+            tiles[row * cols + col] = Tile::Floor;
+
+            if let Some(east_node) = maze.east(node) {
+                if maze.has_link(node, &east_node) {
+                    tiles[row * cols + col + 1] = Tile::Floor;
+                }
+            }
+            if let Some(south_node) = maze.south(node) {
+                if maze.has_link(node, &south_node) {
+                    tiles[(row + 1) * cols + col] = Tile::Floor;
+                }
+            }
+        }
+
+        Self { rows, cols, tiles }
+    }
+
+    /// returns a tile map with the given tiles, stored in row order
+    fn new(rows: usize, cols: usize, tiles: Vec<Tile>) -> Result<Self, TileError> {
+        if rows < 3 || cols < 3 || rows.is_multiple_of(2) || cols.is_multiple_of(2) {
+            return Err(TileError::InvalidDimensions { rows, cols });
+        }
+        Ok(Self { rows, cols, tiles })
+    }
+
+    /// returns the dimensions of the tile map as a (rows, cols) tuple
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.rows, self.cols)
+    }
+
+    /// returns the tile at `row`, `col` or `None` if it is outside of the map
+    pub fn get(&self, row: usize, col: usize) -> Option<Tile> {
+        if row < self.rows && col < self.cols {
+            Some(self.tiles[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    /// returns the rows of tiles, from north to south
+    pub fn iter_rows(&self) -> std::slice::ChunksExact<'_, Tile> {
+        self.tiles.chunks_exact(self.cols)
+    }
+
+    /// returns the tile map as plain text, using `#` for walls and `.` for floors, with one line
+    /// per row
+    pub fn to_text(&self) -> String {
+        let mut buf = String::with_capacity((self.cols + 1) * self.rows);
+        for row in self.iter_rows() {
+            buf.extend(row.iter().map(|tile| tile.to_char()));
+            buf.push('\n');
+        }
+        buf
+    }
+
+    /// parses a tile map from plain text, as written by `to_text`
+    pub fn from_text(text: &str) -> Result<Self, TileError> {
+        let rows: Vec<(usize, Vec<&str>)> = text
+            .lines()
+            .map(|line| line.trim_end())
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| (i, line.char_indices().map(|(j, c)| &line[j..j + c.len_utf8()]).collect()))
+            .collect();
+        Self::from_values(rows, |value| match value {
+            "#" => Some(Tile::Wall),
+            "." => Some(Tile::Floor),
+            _ => None,
+        })
+    }
+
+    /// returns the tile map as comma separated values, with one line per row. Walls are
+    /// written as `1` and floors as `0`
+    pub fn to_csv(&self) -> String {
+        let mut buf = String::new();
+        for row in self.iter_rows() {
+            let values: Vec<&str> = row.iter()
+                .map(|tile| if *tile == Tile::Wall { "1" } else { "0" })
+                .collect();
+            let _ = writeln!(buf, "{}", values.join(","));
+        }
+        buf
+    }
+
+    /// parses a tile map from comma separated values, as written by `to_csv`
+    pub fn from_csv(csv: &str) -> Result<Self, TileError> {
+        let rows: Vec<(usize, Vec<&str>)> = csv
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| (i, line.split(',').map(|value| value.trim()).collect()))
+            .collect();
+        Self::from_values(rows, |value| match value {
+            "1" => Some(Tile::Wall),
+            "0" => Some(Tile::Floor),
+            _ => None,
+        })
+    }
+
+    /// builds a tile map from rows of string values, using `parse` to convert each value to
+    /// a tile. Each row is paired with the index of the line it was read from, which is the row
+    /// reported in errors
+    fn from_values<F>(rows: Vec<(usize, Vec<&str>)>, parse: F) -> Result<Self, TileError>
+    where
+        F: Fn(&str) -> Option<Tile>,
+    {
+        let cols = rows.first().map_or(0, |(_, row)| row.len());
+        let mut tiles = Vec::with_capacity(rows.len() * cols);
+
+        for (line, row) in rows.iter() {
+            if row.len() != cols {
+                return Err(TileError::RaggedRow { row: *line, len: row.len(), expected: cols });
+            }
+            for (c, value) in row.iter().enumerate() {
+                match parse(value) {
+                    Some(tile) => tiles.push(tile),
+                    None => {
+                        return Err(TileError::InvalidTile { row: *line, col: c, value: value.to_string() })
+                    }
+                }
+            }
+        }
+
+        Self::new(rows.len(), cols, tiles)
+    }
+
+    /// returns the tile map as a Tiled TMX (XML) map with a single, CSV encoded, tile layer.
+    /// Walls use tile id 1 and floors use tile id 2 of a two tile tileset named "maze", with tiles
+    /// of `tile_size` pixels. Point the tileset at your own images in Tiled.
+    pub fn to_tmx(&self, tile_size: u32) -> String {
+        let mut buf = String::new();
+        let _ = writeln!(buf, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+        let _ = writeln!(
+            buf,
+            "<map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" width=\"{}\" height=\"{}\" tilewidth=\"{ts}\" tileheight=\"{ts}\" infinite=\"0\" nextlayerid=\"2\" nextobjectid=\"1\">",
+            self.cols, self.rows, ts = tile_size
+        );
+        let _ = writeln!(
+            buf,
+            " <tileset firstgid=\"1\" name=\"maze\" tilewidth=\"{ts}\" tileheight=\"{ts}\" tilecount=\"2\" columns=\"2\"/>",
+            ts = tile_size
+        );
+        let _ = writeln!(buf, " <layer id=\"1\" name=\"maze\" width=\"{}\" height=\"{}\">", self.cols, self.rows);
+        let _ = writeln!(buf, "  <data encoding=\"csv\">");
+        let rows: Vec<String> = self.iter_rows().map(|row| gids(row).join(",")).collect();
+        let _ = writeln!(buf, "{}", rows.join(",\n"));
+        let _ = writeln!(buf, "</data>\n </layer>\n</map>");
+        buf
+    }
+
+    /// returns the tile map as a Tiled JSON map, containing the same map as `to_tmx`
+    pub fn to_tiled_json(&self, tile_size: u32) -> String {
+        let data: Vec<String> = self.iter_rows().flat_map(gids).collect();
+        format!(
+            concat!(
+                "{{\"type\":\"map\",\"version\":\"1.10\",\"orientation\":\"orthogonal\",",
+                "\"renderorder\":\"right-down\",\"width\":{w},\"height\":{h},",
+                "\"tilewidth\":{ts},\"tileheight\":{ts},\"infinite\":false,",
+                "\"nextlayerid\":2,\"nextobjectid\":1,",
+                "\"layers\":[{{\"id\":1,\"name\":\"maze\",\"type\":\"tilelayer\",\"x\":0,\"y\":0,",
+                "\"width\":{w},\"height\":{h},\"opacity\":1,\"visible\":true,\"data\":[{data}]}}],",
+                "\"tilesets\":[{{\"firstgid\":1,\"name\":\"maze\",\"tilewidth\":{ts},\"tileheight\":{ts},",
+                "\"tilecount\":2,\"columns\":2}}]}}\n"
+            ),
+            w = self.cols,
+            h = self.rows,
+            ts = tile_size,
+            data = data.join(",")
+        )
+    }
+
+    /// converts this tile map back into a maze. Every cell tile must be a floor, and the border
+    /// and cell corner tiles must be walls. Floor tiles between two cells become a
+    /// bi-directional link between them.
+    pub fn to_maze(&self) -> Result<GridMaze, TileError> {
+        let maze_rows = self.rows / 2;
+        let maze_cols = self.cols / 2;
+        let mut maze = GridMaze::new(maze_rows, maze_cols)
+            .map_err(|_| TileError::InvalidDimensions { rows: self.rows, cols: self.cols })?;
+
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let tile = self.tiles[row * self.cols + col];
+                let on_border = row == 0 || col == 0 || row == self.rows - 1 || col == self.cols - 1;
+                let expected = match (row % 2, col % 2) {
+                    // the corners between cells
+                    (0, 0) => Some(Tile::Wall),
+                    // a cell
+                    (1, 1) => Some(Tile::Floor),
+                    _ if on_border => Some(Tile::Wall),
+                    // a wall, or passage, between two cells
+                    _ => None,
+                };
+
+                match expected {
+                    Some(expected) if expected != tile => {
+                        return Err(TileError::UnexpectedTile { row, col, expected })
+                    }
+                    Some(_) => {}
+                    None if tile == Tile::Floor => {
+                        // the cells on either side of the passage
+                        let (r1, c1, r2, c2) = if row % 2 == 0 {
+                            (row / 2 - 1, col / 2, row / 2, col / 2)
+                        } else {
+                            (row / 2, col / 2 - 1, row / 2, col / 2)
+                        };
+                        if let (Some(node1), Some(node2)) = (maze.get2d(r1, c1), maze.get2d(r2, c2)) {
+                            maze.link(&node1, &node2, true);
+                        }
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        Ok(maze)
+    }
+}
+
+impl From<&GridMaze> for TileMap {
+    fn from(maze: &GridMaze) -> Self {
+        TileMap::from_maze(maze)
+    }
+}
+
+impl Display for TileMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_text())
+    }
+}
+
+/// returns the Tiled global tile ids of a row of tiles
+fn gids(row: &[Tile]) -> Vec<String> {
+    row.iter()
+        .map(|tile| match tile {
+            Tile::Wall => WALL_GID.to_string(),
+            Tile::Floor => FLOOR_GID.to_string(),
+        })
+        .collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::{TileMap, Tile, TileError};
+    use crate::generator::wilsons;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn should_convert_maze_to_tiles() {
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n3) = (maze[0], maze[1], maze[3]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n3, true);
+        let tiles = TileMap::from_maze(&maze);
+        assert_eq!(tiles.dimensions(), (5, 5));
+        assert_eq!(tiles.to_text(), "#####\n#...#\n###.#\n#.#.#\n#####\n");
+        assert_eq!(tiles.get(1, 2), Some(Tile::Floor));
+        assert_eq!(tiles.get(5, 0), None);
+    }
+
+    #[test]
+    fn text_and_csv_should_round_trip() {
+        let maze = wilsons::generate(6, 7).unwrap();
+        let tiles = TileMap::from_maze(&maze);
+        assert_eq!(TileMap::from_text(&tiles.to_text()), Ok(tiles.clone()));
+        assert_eq!(TileMap::from_csv(&tiles.to_csv()), Ok(tiles.clone()));
+
+        let converted = tiles.to_maze().unwrap();
+        assert_eq!(converted.dimensions(), (6, 7));
+        assert_eq!(converted.to_string(), maze.to_string());
+    }
+
+    #[test]
+    fn should_reject_invalid_tile_maps() {
+        assert_eq!(
+            TileMap::from_text("####\n#..#\n####\n"),
+            Err(TileError::InvalidDimensions { rows: 3, cols: 4 })
+        );
+        assert_eq!(
+            TileMap::from_text("###\n#x#\n###\n"),
+            Err(TileError::InvalidTile { row: 1, col: 1, value: String::from("x") })
+        );
+        assert_eq!(
+            TileMap::from_text("###\n#.#\n##\n"),
+            Err(TileError::RaggedRow { row: 2, len: 2, expected: 3 })
+        );
+        // rows are reported by their line in the input, blank lines included
+        assert_eq!(
+            TileMap::from_text("\n###\n\n#x#\n###\n"),
+            Err(TileError::InvalidTile { row: 3, col: 1, value: String::from("x") })
+        );
+        assert_eq!(
+            TileMap::from_csv("1,1,1\n\n1,0,1\n1,1\n"),
+            Err(TileError::RaggedRow { row: 3, len: 2, expected: 3 })
+        );
+        let open_border = TileMap::from_text("###\n..#\n###\n").unwrap();
+        assert_eq!(
+            open_border.to_maze().unwrap_err(),
+            TileError::UnexpectedTile { row: 1, col: 0, expected: Tile::Wall }
+        );
+    }
+
+    #[test]
+    fn should_export_tiled_formats() {
+        let tiles = TileMap::from_maze(&GridMaze::new(1, 1).unwrap());
+        let tmx = tiles.to_tmx(16);
+        assert!(tmx.contains("width=\"3\" height=\"3\""));
+        assert!(tmx.contains("1,1,1,\n1,2,1,\n1,1,1\n"));
+        let json = tiles.to_tiled_json(16);
+        assert!(json.contains("\"data\":[1,1,1,1,2,1,1,1,1]"));
+    }
+}
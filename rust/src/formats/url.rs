@@ -0,0 +1,346 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::formats::binary::{pack_links, unpack_links, link_bytes_len, DecodeError, EncodeError, Topology};
+use crate::maze::grid_maze::GridMaze;
+
+/// the version prefix of strings written by `encode`
+pub const VERSION_PREFIX: &str = "1.";
+
+/// the version prefix of strings written by `encode_compressed`
+pub const COMPRESSED_VERSION_PREFIX: &str = "2.";
+
+/// the longest run of repeated bytes, and the longest literal, a single run can hold
+const MAX_REPEAT: usize = 130;
+const MAX_LITERAL: usize = 128;
+
+/// the URL and filename safe base64 alphabet (RFC 4648 §5)
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// UrlError is returned when a string can't be decoded into a maze
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrlError {
+    /// the string does not start with a version prefix this crate knows about
+    UnsupportedVersion(String),
+    /// the character at byte `index` of the string is not in the URL-safe base64 alphabet
+    InvalidCharacter { index: usize, found: char },
+    /// the string ends before the dimensions of the maze
+    Truncated,
+    /// the base64 is not as `encode` writes it: it ends with a lone character, or its last
+    /// character has bits set that are not part of any byte
+    NonCanonical,
+    /// the topology, dimensions or links of the maze are invalid
+    Decode(DecodeError),
+}
+
+impl Display for UrlError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UrlError::UnsupportedVersion(prefix) => write!(f, "unsupported maze string version {:?}", prefix),
+            UrlError::InvalidCharacter { index, found } => {
+                write!(f, "invalid character {:?} at index {}", found, index)
+            }
+            UrlError::Truncated => write!(f, "the maze string is truncated"),
+            UrlError::NonCanonical => write!(f, "the maze string is not canonical base64"),
+            UrlError::Decode(err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for UrlError {}
+
+impl From<DecodeError> for UrlError {
+    fn from(err: DecodeError) -> Self {
+        UrlError::Decode(err)
+    }
+}
+
+/// encodes the `maze` into a short string that can be pasted into a chat message, or used as
+/// a URL query parameter, without any escaping.
+///
+/// The string is the version prefix `1.` followed by the URL-safe base64 (without padding) of:
+///
+/// * the topology byte, 0 = grid
+/// * rows and cols, as LEB128 variable length integers
+/// * the link bits, two per cell, packed as in the `binary` format
+///
+/// A 20x20 maze takes 140 characters. The link bits of most generated mazes are close to random,
+/// so they are not compressed, see `encode_compressed` for mazes with more regular passages.
+/// Like the `binary` format, the maze must only contain bi-directional links between neighbors.
+pub fn encode(maze: &GridMaze) -> Result<String, EncodeError> {
+    let (mut bytes, links) = header_and_links(maze)?;
+    bytes.extend_from_slice(&links);
+    Ok(to_base64(VERSION_PREFIX, &bytes))
+}
+
+/// encodes the `maze` like `encode`, but run-length encodes the link bytes, and uses the version
+/// prefix `2.`. The runs are:
+///
+/// * a control byte `c` below 128, followed by `c + 1` literal bytes
+/// * a control byte `c` of 128 or more, followed by one byte that is repeated `c - 125` times
+///
+/// Mazes with long straight passages, i.e. binary tree or sidewinder mazes, or mazes that have
+/// few passages, get much shorter. A maze with random link bits grows by at most one byte for
+/// every 128 bytes of links.
+pub fn encode_compressed(maze: &GridMaze) -> Result<String, EncodeError> {
+    let (mut bytes, links) = header_and_links(maze)?;
+    compress(&links, &mut bytes);
+    Ok(to_base64(COMPRESSED_VERSION_PREFIX, &bytes))
+}
+
+/// returns the topology byte and dimensions of the `maze`, and its packed link bits
+fn header_and_links(maze: &GridMaze) -> Result<(Vec<u8>, Vec<u8>), EncodeError> {
+    let (rows, cols) = maze.dimensions();
+    let mut bytes = vec![Topology::Grid.to_byte()];
+    write_varint(&mut bytes, rows as u64);
+    write_varint(&mut bytes, cols as u64);
+    Ok((bytes, pack_links(maze)?))
+}
+
+/// returns `prefix` followed by the URL-safe base64 of `bytes`
+fn to_base64(prefix: &str, bytes: &[u8]) -> String {
+    let mut buf = String::from(prefix);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        // every byte of the chunk needs one more character than it has bytes
+        for i in 0..=chunk.len() {
+            buf.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+        }
+    }
+    buf
+}
+
+/// decodes a maze from a string written by `encode` or `encode_compressed`
+pub fn decode(text: &str) -> Result<GridMaze, UrlError> {
+    let (payload, compressed) = match (text.strip_prefix(VERSION_PREFIX), text.strip_prefix(COMPRESSED_VERSION_PREFIX)) {
+        (Some(payload), _) => (payload, false),
+        (_, Some(payload)) => (payload, true),
+        _ => {
+            let prefix = text.split('.').next().unwrap_or_default();
+            return Err(UrlError::UnsupportedVersion(prefix.to_string()));
+        }
+    };
+
+    // decode the base64, both version prefixes are the same length
+    let mut bytes = Vec::with_capacity(payload.len() * 3 / 4);
+    let (mut bits, mut n_bits) = (0u32, 0);
+    for (i, c) in payload.char_indices() {
+        let value = ALPHABET.iter()
+            .position(|a| *a as char == c)
+            .ok_or(UrlError::InvalidCharacter { index: VERSION_PREFIX.len() + i, found: c })?;
+        bits = (bits << 6) | value as u32;
+        n_bits += 6;
+        if n_bits >= 8 {
+            n_bits -= 8;
+            bytes.push((bits >> n_bits) as u8);
+            bits &= (1 << n_bits) - 1;
+        }
+    }
+    // a lone final character holds 6 bits of a byte, and the bits left over after the last byte
+    // are always 0, so there is only one way to write each maze
+    if n_bits >= 6 || bits != 0 {
+        return Err(UrlError::NonCanonical);
+    }
+
+    let (&topology, mut rest) = bytes.split_first().ok_or(UrlError::Truncated)?;
+    Topology::from_byte(topology).ok_or(DecodeError::UnknownTopology(topology))?;
+    let rows = read_varint(&mut rest).ok_or(UrlError::Truncated)?;
+    let cols = read_varint(&mut rest).ok_or(UrlError::Truncated)?;
+
+    let expected = rows.checked_mul(cols).map(link_bytes_len);
+    let links = if compressed {
+        decompress(rest, expected.unwrap_or(usize::MAX))?
+    } else {
+        rest.to_vec()
+    };
+    if expected != Some(links.len()) {
+        return Err(DecodeError::LengthMismatch {
+            expected: expected.unwrap_or(usize::MAX),
+            found: links.len(),
+        }.into());
+    }
+    Ok(unpack_links(rows, cols, &links)?)
+}
+
+/// appends the run-length encoding of `links` to `bytes`, see `encode_compressed` for the runs
+fn compress(links: &[u8], bytes: &mut Vec<u8>) {
+    let mut literal_start = 0;
+    let mut i = 0;
+    while i < links.len() {
+        let run = links[i..].iter().take(MAX_REPEAT).take_while(|b| **b == links[i]).count();
+        if run >= 3 {
+            push_literals(&links[literal_start..i], bytes);
+            bytes.push((run + 125) as u8);
+            bytes.push(links[i]);
+            i += run;
+            literal_start = i;
+        } else {
+            i += 1;
+        }
+    }
+    push_literals(&links[literal_start..], bytes);
+}
+
+/// appends `literals` to `bytes` as literal runs
+fn push_literals(literals: &[u8], bytes: &mut Vec<u8>) {
+    for chunk in literals.chunks(MAX_LITERAL) {
+        bytes.push((chunk.len() - 1) as u8);
+        bytes.extend_from_slice(chunk);
+    }
+}
+
+/// returns the link bytes run-length encoded in `runs`. Decoding stops once there are more than
+/// `expected` bytes, so a short string can't expand into a huge allocation
+fn decompress(mut runs: &[u8], expected: usize) -> Result<Vec<u8>, UrlError> {
+    let mut links = Vec::new();
+    while let Some((&control, rest)) = runs.split_first() {
+        let control = control as usize;
+        if control < MAX_LITERAL {
+            let literals = rest.get(..control + 1).ok_or(UrlError::Truncated)?;
+            links.extend_from_slice(literals);
+            runs = &rest[control + 1..];
+        } else {
+            let (&byte, rest) = rest.split_first().ok_or(UrlError::Truncated)?;
+            links.extend(std::iter::repeat_n(byte, control - 125));
+            runs = rest;
+        }
+        if links.len() > expected {
+            break;
+        }
+    }
+    Ok(links)
+}
+
+/// appends `n` to `bytes` as an unsigned LEB128 integer
+fn write_varint(bytes: &mut Vec<u8>, mut n: u64) {
+    while n >= 0x80 {
+        bytes.push((n as u8 & 0x7F) | 0x80);
+        n >>= 7;
+    }
+    bytes.push(n as u8);
+}
+
+/// reads an unsigned LEB128 integer from the front of `bytes`, advancing it past the integer.
+/// Returns `None` if `bytes` ends inside the integer, or it doesn't fit in a usize
+fn read_varint(bytes: &mut &[u8]) -> Option<usize> {
+    let mut n: usize = 0;
+    for (i, b) in bytes.iter().enumerate() {
+        let shift = 7 * i as u32;
+        let low = (*b & 0x7F) as usize;
+        if shift >= usize::BITS || (low << shift) >> shift != low {
+            return None;
+        }
+        n |= low << shift;
+        if b & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Some(n);
+        }
+    }
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{encode, encode_compressed, decode, UrlError, ALPHABET};
+    use crate::formats::binary::DecodeError;
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn every_generator_should_round_trip() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            // include dimensions that need multi-byte varints and partial base64 chunks
+            let (rows, cols) = [(20, 20), (1, 130), (3, 7), (9, 2)][i % 4];
+            let mut maze = algo.generate_with_rng(rows, cols, StdRng::seed_from_u64(i as u64)).unwrap();
+            if i % 2 == 0 {
+                maze.braid(0.5);
+            }
+            let text = encode(&maze).unwrap();
+            assert!(text.starts_with("1."));
+            assert!(text[2..].chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+            let decoded = decode(&text).unwrap();
+            assert_eq!(decoded.to_string(), maze.to_string(), "{} did not round trip", algo);
+            assert_eq!(encode(&decoded).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn twenty_by_twenty_maze_should_be_short() {
+        let maze = Algorithm::Wilsons.generate_with_rng(20, 20, StdRng::seed_from_u64(1)).unwrap();
+        assert_eq!(encode(&maze).unwrap().len(), 140);
+    }
+
+    #[test]
+    fn should_reject_invalid_strings() {
+        let text = encode(&GridMaze::new(3, 3).unwrap()).unwrap();
+        assert_eq!(decode(&text.replacen("1.", "3.", 1)).unwrap_err(), UrlError::UnsupportedVersion("3".to_string()));
+        assert_eq!(decode("1.AA*A").unwrap_err(), UrlError::InvalidCharacter { index: 4, found: '*' });
+        assert_eq!(decode("1.").unwrap_err(), UrlError::Truncated);
+        assert!(matches!(
+            decode(&text[..text.len() - 2]),
+            Err(UrlError::Decode(DecodeError::LengthMismatch { .. }))
+        ));
+    }
+
+    #[test]
+    fn should_reject_non_canonical_base64() {
+        // a 1x1 maze is 4 bytes, so its last character has 4 bits that are not part of a byte
+        let text = encode(&GridMaze::new(1, 1).unwrap()).unwrap();
+        assert_eq!(text.len(), 8);
+        let last = ALPHABET.iter().position(|a| *a as char == text.chars().last().unwrap()).unwrap();
+        let trailing_bits = format!("{}{}", &text[..7], ALPHABET[last | 1] as char);
+        assert_eq!(decode(&trailing_bits).unwrap_err(), UrlError::NonCanonical);
+        // 9 characters, the last of which is a lone character
+        assert_eq!(decode(&format!("{}AAA", text)).unwrap_err(), UrlError::NonCanonical);
+        assert!(decode(&text).is_ok());
+    }
+
+    #[test]
+    fn every_generator_should_round_trip_compressed() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let (rows, cols) = [(20, 20), (1, 130), (3, 7), (9, 2)][i % 4];
+            let maze = algo.generate_with_rng(rows, cols, StdRng::seed_from_u64(i as u64)).unwrap();
+            let text = encode_compressed(&maze).unwrap();
+            assert!(text.starts_with("2."));
+
+            let decoded = decode(&text).unwrap();
+            assert_eq!(decoded.to_string(), maze.to_string(), "{} did not round trip", algo);
+            assert_eq!(encode_compressed(&decoded).unwrap(), text);
+        }
+    }
+
+    #[test]
+    fn compression_should_shorten_regular_mazes() {
+        // every link of an unlinked maze is 0, a single run
+        let empty = GridMaze::new(40, 40).unwrap();
+        assert!(encode_compressed(&empty).unwrap().len() < 20);
+        assert_eq!(decode(&encode_compressed(&empty).unwrap()).unwrap().to_string(), empty.to_string());
+
+        // the top row of a binary tree maze is one long corridor
+        let maze = Algorithm::BinaryTree.generate_with_rng(4, 300, StdRng::seed_from_u64(1)).unwrap();
+        assert!(encode_compressed(&maze).unwrap().len() < encode(&maze).unwrap().len());
+
+        // random links grow by at most one byte in 128
+        let maze = Algorithm::Wilsons.generate_with_rng(20, 20, StdRng::seed_from_u64(1)).unwrap();
+        assert!(encode_compressed(&maze).unwrap().len() <= encode(&maze).unwrap().len() + 4);
+    }
+
+    #[test]
+    fn should_reject_invalid_compressed_strings() {
+        let text = encode_compressed(&GridMaze::new(30, 30).unwrap()).unwrap();
+        assert!(matches!(
+            decode(&text[..text.len() - 2]),
+            Err(UrlError::Truncated) | Err(UrlError::Decode(DecodeError::LengthMismatch { .. }))
+        ));
+        // runs that expand past the links of a 1x1 maze
+        let mut bytes = vec![0, 1, 1];
+        bytes.extend_from_slice(&[255; 30]);
+        let text = super::to_base64("2.", &bytes);
+        assert!(matches!(decode(&text), Err(UrlError::Decode(DecodeError::LengthMismatch { .. }))));
+    }
+}
@@ -1,7 +1,10 @@
-
+use arrayvec::ArrayVec;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
+use crate::maze::grid_node::GridNode;
 
 /// Generates a random maze using the Aldous-Broder algorithm.
 /// Aldous-Broder generates mazes using "random-walks". This avoids creating mazes
@@ -12,29 +15,81 @@ use crate::maze::grid_maze::GridMaze;
 /// 1. Start anywhere in the maze you want, and choose a random neighbor.
 /// 2. Move to that neighbor, and if it has not previously been visited, link it to the prior node.
 /// 3. Repeat until every node has been visited.
-pub fn generate(height: usize, width: usize) -> GridMaze {
-    let mut maze = GridMaze::new(height, width);
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(AldousBroder::new(height, width, thread_rng())?.finish())
+}
 
-    // start at a random node position
-    let mut cur_node = maze.random_node();
-    let mut unvisited = maze.len() - 1;
+/// Step-wise version of the Aldous-Broder algorithm, see [`generate`] for a description of the
+/// algorithm. Each step moves to a random neighbor and returns `Event::Link` if the neighbor was
+/// unvisited (and therefore linked to), else `Event::Visit`
+pub struct AldousBroder<R> {
+    maze: GridMaze,
+    rng: R,
+    // the node the random walk is currently on, `None` until the first step is taken
+    cur_node: Option<GridNode>,
+    unvisited: usize,
+}
 
-    while unvisited > 0 {
-        // choose a random neighbor of the current_node
-        let rand_neighbor = *maze
-            .neighbors(&cur_node)
-            .choose(&mut thread_rng())
-            .expect("all nodes in a maze will have at least 2 neighbors");
+impl<R: Rng> AldousBroder<R> {
+    /// returns a new Aldous-Broder generator for a maze of `height` rows and `width` columns,
+    /// using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, rng: R) -> Result<Self, MazeError> {
+        let maze = GridMaze::new(height, width)?;
+        let unvisited = maze.len();
+        Ok(Self {
+            maze,
+            rng,
+            cur_node: None,
+            unvisited,
+        })
+    }
+}
+
+impl<R: Rng> Iterator for AldousBroder<R> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.unvisited == 0 {
+            return None;
+        }
+
+        let cur_node = match self.cur_node {
+            Some(node) => node,
+            None => {
+                // start at a random node position
+                let start = self.maze.random_node_with(&mut self.rng);
+                self.cur_node = Some(start);
+                self.unvisited -= 1;
+                return Some(Event::Visit(start));
+            }
+        };
+
+        // choose a random neighbor of the current_node. Only the node of a 1x1 maze has no
+        // neighbors, and that maze is complete as soon as its node is visited
+        let rand_neighbor = *self.maze
+            .neighbors_iter(&cur_node)
+            .collect::<ArrayVec<GridNode, 4>>()
+            .choose(&mut self.rng)?;
+        self.cur_node = Some(rand_neighbor);
 
         // if the rand_neighbor is not linked to anything (i.e. it is unvisited), then link it
         // to the current node
-        if maze.get_links(&rand_neighbor).is_empty() {
-            maze.link(&cur_node, &rand_neighbor, true);
-            unvisited -= 1;
+        if self.maze.get_links(&rand_neighbor).is_empty() {
+            self.maze.link(&cur_node, &rand_neighbor, true);
+            self.unvisited -= 1;
+            Some(Event::Link(cur_node, rand_neighbor))
+        } else {
+            Some(Event::Visit(rand_neighbor))
         }
+    }
+}
 
-        cur_node = rand_neighbor;
+impl<R: Rng> Steps for AldousBroder<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
     }
 
-    maze
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
 }
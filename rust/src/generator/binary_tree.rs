@@ -1,7 +1,9 @@
 
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
 
 /// Generates a random maze using the Binary Tree algorithm.
 ///
@@ -10,31 +12,72 @@ use crate::maze::grid_maze::GridMaze;
 /// 2. iterate through the nodes row by row
 /// 3. for each node pick a random East or South wall to remove
 /// 4. repeat until all nodes have been visited
-pub fn generate(height: usize, width: usize) -> GridMaze {
-    let mut maze = GridMaze::new(height, width);
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(BinaryTree::new(height, width, thread_rng())?.finish())
+}
+
+/// Step-wise version of the Binary Tree algorithm, see [`generate`] for a description of the
+/// algorithm. Each step processes one node, returning `Event::Link` when a wall was removed, or
+/// `Event::Visit` when the node has no east or south neighbor to link to
+pub struct BinaryTree<R> {
+    maze: GridMaze,
+    rng: R,
+    // index of the next node to process
+    node_index: usize,
+}
+
+impl<R: Rng> BinaryTree<R> {
+    /// returns a new Binary Tree generator for a maze of `height` rows and `width` columns,
+    /// using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, rng: R) -> Result<Self, MazeError> {
+        Ok(Self {
+            maze: GridMaze::new(height, width)?,
+            rng,
+            node_index: 0,
+        })
+    }
+}
 
-    // iterate over node indices
-    for node_index in 0..maze.len() {
+impl<R: Rng> Iterator for BinaryTree<R> {
+    type Item = Event;
 
-        let curr_node = maze[node_index];
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.node_index >= self.maze.len() {
+            return None;
+        }
+
+        let curr_node = self.maze[self.node_index];
+        self.node_index += 1;
 
         let mut neighbors = vec![];
 
         // if current cell has a south neighbor, add that neighbors position to neighbors
-        if let Some(south_neighbor) = maze.south(&curr_node) {
+        if let Some(south_neighbor) = self.maze.south(&curr_node) {
             neighbors.push(south_neighbor);
         }
 
         // if current cell has a east neighbor, add that neighbors position to neighbors
-        if let Some(east_neighbor) = maze.east(&curr_node) {
+        if let Some(east_neighbor) = self.maze.east(&curr_node) {
             neighbors.push(east_neighbor);
         }
 
         // choose a random neighbor from neighbors and create a link to it
-        if let Some(rand_neighbor) = neighbors.choose(&mut thread_rng()) {
-            maze.link(&curr_node, rand_neighbor, true);
+        match neighbors.choose(&mut self.rng) {
+            Some(rand_neighbor) => {
+                self.maze.link(&curr_node, rand_neighbor, true);
+                Some(Event::Link(curr_node, *rand_neighbor))
+            }
+            None => Some(Event::Visit(curr_node)),
         }
     }
+}
+
+impl<R: Rng> Steps for BinaryTree<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
+    }
 
-    maze
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
 }
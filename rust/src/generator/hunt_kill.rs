@@ -1,6 +1,9 @@
+use arrayvec::ArrayVec;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
 use crate::maze::grid_node::GridNode;
 
 
@@ -14,77 +17,144 @@ use crate::maze::grid_node::GridNode;
 ///
 /// Hunt-and-Kill is known to produce mazes with longer winding and meandering corridors than
 /// other algorithms. That is to say, hunt-and-kill produces mazes with fewer dead ends.
-pub fn generate(height: usize, width: usize) -> GridMaze {
-    let mut maze = GridMaze::new(height, width);
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(HuntKill::new(height, width, thread_rng())?.finish())
+}
 
-    // choose a random start node in the maze to begin the random walk
-    let mut next_node = Some(maze.random_node());
+/// the phase the hunt-and-kill generator is currently in
+enum Phase {
+    // the generator has not chosen its starting node yet
+    Start,
+    // randomly walking, starting from the node
+    Walk(GridNode),
+    // hunting for an unvisited node, the next node to examine is at the index
+    Hunt(usize),
+    // all nodes have been visited
+    Done,
+}
 
-    // continue walking until all nodes are visited
-    while let Some(cur_node) = next_node {
+/// Step-wise version of the Hunt-and-Kill algorithm, see [`generate`] for a description of the
+/// algorithm. Random walk steps return `Event::Link`, while every node examined during the hunt
+/// phase is returned as an `Event::HuntScan`. The node found by a hunt is linked into the maze
+/// and returned as an `Event::Link`
+///
+/// Nodes never become unvisited again, so a hunt begins at the first unvisited node rather than
+/// at the top of the maze. The visited nodes before it are skipped without being scanned
+pub struct HuntKill<R> {
+    maze: GridMaze,
+    rng: R,
+    phase: Phase,
+    // the index of the first node that may be unvisited, it only ever moves forward
+    hunt_cursor: usize,
+}
 
-        let unvisited_neighbors = unvisited_neighbors(&maze, &cur_node);
+impl<R: Rng> HuntKill<R> {
+    /// returns a new Hunt-and-Kill generator for a maze of `height` rows and `width` columns,
+    /// using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, rng: R) -> Result<Self, MazeError> {
+        Ok(Self {
+            maze: GridMaze::new(height, width)?,
+            rng,
+            phase: Phase::Start,
+            hunt_cursor: 0,
+        })
+    }
+
+    /// performs one random walk step from `cur_node`. If `cur_node` has no unvisited neighbors,
+    /// the hunt phase begins
+    fn walk(&mut self, cur_node: GridNode) -> Option<Event> {
+        let unvisited_neighbors = unvisited_neighbors(&self.maze, &cur_node);
 
         // this is the random walk, if the cur_node has unvisited neighbors, we will link
         // the cur_node to a random unvisited neighbor and then make that random neighbor
         // the cur_node
-        if !unvisited_neighbors.is_empty() {
-            if let Some(rand_neighbor_node) = unvisited_neighbors.choose(&mut thread_rng()) {
-                maze.link(&cur_node, rand_neighbor_node, true);
-                next_node = Some(*rand_neighbor_node);
+        match unvisited_neighbors.choose(&mut self.rng) {
+            Some(rand_neighbor_node) => {
+                self.maze.link(&cur_node, rand_neighbor_node, true);
+                self.phase = Phase::Walk(*rand_neighbor_node);
+                Some(Event::Link(cur_node, *rand_neighbor_node))
             }
-        } else {
-            // else we begin the hunt phase, starting from the top of the maze, looking for the
-            // first node that is unvisited AND has neighbors that are visited
+            None => {
+                while self.hunt_cursor < self.maze.len()
+                    && !self.maze.get_links(&self.maze[self.hunt_cursor]).is_empty()
+                {
+                    self.hunt_cursor += 1;
+                }
+                self.hunt(self.hunt_cursor)
+            }
+        }
+    }
 
-            next_node = None;
+    /// examines the node at `cur_index` during the hunt phase, looking for the first node that is
+    /// unvisited AND has neighbors that are visited
+    fn hunt(&mut self, cur_index: usize) -> Option<Event> {
+        if cur_index >= self.maze.len() {
+            // no unvisited nodes were found, the maze is complete
+            self.phase = Phase::Done;
+            return None;
+        }
 
-            // begin iterating the maze by index starting at the top-left
-            for cur_index in 0..maze.len() {
+        let cur_hunt_node = self.maze[cur_index];
 
-                let cur_hunt_node = maze[cur_index];
+        // get any visited neighbors of the node at cur_index
+        let visited_neighbors = visited_neighbors(&self.maze, &cur_hunt_node);
 
-                // get any visited neighbors of the node at cur_index
-                let visited_neighbors = visited_neighbors(&maze, &cur_hunt_node);
+        // if the current hunt node is unvisited BUT one of its neighbors is visited, then
+        // link the current_hunt_node to the random neighbor node and set the next_node to
+        // visit (in the random walk) to the current hunt node
+        if self.maze.get_links(&cur_hunt_node).is_empty() {
+            if let Some(rand_neighbor_node) = visited_neighbors.choose(&mut self.rng) {
+                self.maze.link(&cur_hunt_node, rand_neighbor_node, true);
+                self.phase = Phase::Walk(cur_hunt_node);
+                return Some(Event::Link(cur_hunt_node, *rand_neighbor_node));
+            }
+        }
 
-                // if the current hunt node is unvisited BUT one of its neighbors is visited, then
-                // link the current_hunt_node to the random neighbor node and set the next_node to
-                // visit (in the random walk) to the current hunt node
-                if maze.get_links(&cur_hunt_node).is_empty() && !visited_neighbors.is_empty() {
-                    next_node = Some(cur_hunt_node);
+        self.phase = Phase::Hunt(cur_index + 1);
+        Some(Event::HuntScan(cur_hunt_node))
+    }
+}
 
-                    if let Some(rand_neighbor_node) = visited_neighbors.choose(&mut thread_rng()) {
-                        maze.link(&cur_hunt_node, rand_neighbor_node, true);
-                    }
-                    // break so we can repeat the random walk
-                    break;
-                }
+impl<R: Rng> Iterator for HuntKill<R> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.phase {
+            Phase::Start => {
+                // choose a random start node in the maze to begin the random walk
+                let start = self.maze.random_node_with(&mut self.rng);
+                self.phase = Phase::Walk(start);
+                Some(Event::Visit(start))
             }
+            Phase::Walk(cur_node) => self.walk(cur_node),
+            Phase::Hunt(cur_index) => self.hunt(cur_index),
+            Phase::Done => None,
         }
     }
+}
 
-    maze
+impl<R: Rng> Steps for HuntKill<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
+    }
+
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
 }
 
-/// Returns a vector of nodes that are neighbors of the given `node` AND that do not have links to
+/// Returns the nodes that are neighbors of the given `node` AND that do not have links to
 /// any other nodes in the `maze`
-fn unvisited_neighbors(maze: &GridMaze, node: &GridNode) -> Vec<GridNode> {
-    maze
-        .neighbors(node)
-        .iter()
-        .filter(|&neighbor_node| maze.get_links(neighbor_node).is_empty())
-        .copied()
-        .collect()
+fn unvisited_neighbors(maze: &GridMaze, node: &GridNode) -> ArrayVec<GridNode, 4> {
+    maze.unlinked_neighbors_iter(node).collect()
 }
 
 
-/// Returns a vector of positions that are neighbors of the given `node` AND that have a link
+/// Returns the nodes that are neighbors of the given `node` AND that have a link
 /// to another node in the maze
-fn visited_neighbors(maze: &GridMaze, node: &GridNode) -> Vec<GridNode> {
+fn visited_neighbors(maze: &GridMaze, node: &GridNode) -> ArrayVec<GridNode, 4> {
     maze
-        .neighbors(node)
-        .iter()
-        .filter(|&neighbor_node| !maze.get_links(neighbor_node).is_empty())
-        .copied()
+        .neighbors_iter(node)
+        .filter(|neighbor_node| !maze.get_links(neighbor_node).is_empty())
         .collect()
-}
\ No newline at end of file
+}
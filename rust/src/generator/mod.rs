@@ -1,4 +1,21 @@
-/// Modules that generate random mazes
+//! Modules that generate random mazes
+//!
+//! Every generator can be run to completion with its `generate` function, or one step at a time
+//! through its step-wise struct (i.e. `aldous_broder::AldousBroder`). The step-wise structs are
+//! iterators that perform a single step of their algorithm on each call to `next()` and return an
+//! `Event` describing what that step did. This makes it possible to animate, or single-step,
+//! any of the algorithms.
+//!
+//! Creating a generator fails with a `MazeError` if the maze can't be created, i.e. when its
+//! height or width is 0. Any other size, including 1x1 and single row or column mazes, produces
+//! a perfect maze.
+
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use rand::Rng;
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::maze::error::MazeError;
 
 pub mod aldous_broder;
 pub mod binary_tree;
@@ -8,3 +25,283 @@ pub mod hunt_kill;
 pub mod recursive_backtracker;
 pub mod prims;
 pub mod recursive_division;
+pub mod tiled;
+
+#[cfg(test)]
+mod property_tests;
+#[cfg(test)]
+mod uniformity_tests;
+
+/// Event describes a single step taken by a maze generator
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Event {
+    /// the generator moved into (visited) the node
+    Visit(GridNode),
+    /// a passage was carved from the first node to the second node, i.e. the wall between
+    /// them was removed
+    Link(GridNode, GridNode),
+    /// a wall was added between the two nodes, removing the passage between them. Only emitted
+    /// by "wall adding" algorithms, like recursive division
+    Unlink(GridNode, GridNode),
+    /// the generator backed out of the node, because the node has no more unvisited neighbors
+    Backtrack(GridNode),
+    /// the node was examined while hunting for an unvisited node (hunt-and-kill only)
+    HuntScan(GridNode),
+}
+
+/// Steps is implemented by all step-wise maze generators. Each call to `next()` performs one
+/// step of the generation algorithm and returns the `Event` describing that step. The iterator
+/// is exhausted once the maze has been completely generated.
+pub trait Steps: Iterator<Item = Event> {
+    /// returns a reference to the maze being generated, in its current state
+    fn maze(&self) -> &GridMaze;
+
+    /// consumes the generator and returns the maze in its current state
+    fn into_maze(self) -> GridMaze where Self: Sized;
+
+    /// runs all remaining steps of the generator and returns the finished maze
+    fn finish(mut self) -> GridMaze where Self: Sized {
+        self.by_ref().for_each(drop);
+        self.into_maze()
+    }
+}
+
+
+/// Algorithm enumerates the maze generation algorithms provided by the `generator` modules, so
+/// that a generator can be chosen at runtime, i.e. by name from the command line.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    AldousBroder,
+    BinaryTree,
+    HuntKill,
+    Prims,
+    RecursiveBacktracker,
+    RecursiveDivision,
+    Sidewinder,
+    Wilsons,
+}
+
+impl Algorithm {
+    /// all of the available algorithms
+    pub const ALL: [Algorithm; 8] = [
+        Algorithm::AldousBroder,
+        Algorithm::BinaryTree,
+        Algorithm::HuntKill,
+        Algorithm::Prims,
+        Algorithm::RecursiveBacktracker,
+        Algorithm::RecursiveDivision,
+        Algorithm::Sidewinder,
+        Algorithm::Wilsons,
+    ];
+
+    /// returns the name of the algorithm, which is the same as the name of its module
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::AldousBroder => "aldous_broder",
+            Algorithm::BinaryTree => "binary_tree",
+            Algorithm::HuntKill => "hunt_kill",
+            Algorithm::Prims => "prims",
+            Algorithm::RecursiveBacktracker => "recursive_backtracker",
+            Algorithm::RecursiveDivision => "recursive_division",
+            Algorithm::Sidewinder => "sidewinder",
+            Algorithm::Wilsons => "wilsons",
+        }
+    }
+
+    /// returns a step-wise generator, for a maze of `height` rows and `width` columns, that
+    /// uses this algorithm and `rng` as its source of randomness
+    pub fn steps<'a, R: Rng + 'a>(&self, height: usize, width: usize, rng: R) -> Result<Box<dyn Steps + 'a>, MazeError> {
+        let steps: Box<dyn Steps + 'a> = match self {
+            Algorithm::AldousBroder => Box::new(aldous_broder::AldousBroder::new(height, width, rng)?),
+            Algorithm::BinaryTree => Box::new(binary_tree::BinaryTree::new(height, width, rng)?),
+            Algorithm::HuntKill => Box::new(hunt_kill::HuntKill::new(height, width, rng)?),
+            Algorithm::Prims => Box::new(prims::Prims::new(height, width, rng)?),
+            Algorithm::RecursiveBacktracker => {
+                Box::new(recursive_backtracker::RecursiveBacktracker::new(height, width, rng)?)
+            }
+            Algorithm::RecursiveDivision => {
+                Box::new(recursive_division::RecursiveDivision::new(height, width, rng)?)
+            }
+            Algorithm::Sidewinder => Box::new(sidewinder::Sidewinder::new(height, width, rng)?),
+            Algorithm::Wilsons => Box::new(wilsons::Wilsons::new(height, width, rng)?),
+        };
+        Ok(steps)
+    }
+
+    /// generates a complete maze of `height` rows and `width` columns using this algorithm and
+    /// `rng` as the source of randomness
+    pub fn generate_with_rng<R: Rng>(&self, height: usize, width: usize, rng: R) -> Result<GridMaze, MazeError> {
+        let maze = match self {
+            Algorithm::AldousBroder => aldous_broder::AldousBroder::new(height, width, rng)?.finish(),
+            Algorithm::BinaryTree => binary_tree::BinaryTree::new(height, width, rng)?.finish(),
+            Algorithm::HuntKill => hunt_kill::HuntKill::new(height, width, rng)?.finish(),
+            Algorithm::Prims => prims::Prims::new(height, width, rng)?.finish(),
+            Algorithm::RecursiveBacktracker => {
+                recursive_backtracker::RecursiveBacktracker::new(height, width, rng)?.finish()
+            }
+            Algorithm::RecursiveDivision => {
+                recursive_division::RecursiveDivision::new(height, width, rng)?.finish()
+            }
+            Algorithm::Sidewinder => sidewinder::Sidewinder::new(height, width, rng)?.finish(),
+            Algorithm::Wilsons => wilsons::Wilsons::new(height, width, rng)?.finish(),
+        };
+        Ok(maze)
+    }
+}
+
+impl Display for Algorithm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// parses an algorithm from its name. Dashes are accepted in place of underscores, so both
+/// "hunt_kill" and "hunt-kill" parse to `Algorithm::HuntKill`
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().to_lowercase().replace('-', "_");
+        Algorithm::ALL
+            .iter()
+            .find(|algo| algo.name() == name)
+            .copied()
+            .ok_or_else(|| format!("unknown maze algorithm: {}", s))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{Algorithm, Event, Steps};
+    use crate::maze::error::MazeError;
+    use super::binary_tree::BinaryTree;
+    use super::recursive_backtracker::RecursiveBacktracker;
+    use super::hunt_kill::HuntKill;
+    use super::sidewinder::Sidewinder;
+    use super::tiled;
+
+    #[test]
+    fn binary_tree_in_a_single_row_should_carve_east() {
+        let mut steps = BinaryTree::new(1, 3, StdRng::seed_from_u64(1)).unwrap();
+        let (n0, n1, n2) = (steps.maze()[0], steps.maze()[1], steps.maze()[2]);
+        let events: Vec<Event> = steps.by_ref().collect();
+        assert_eq!(events, vec![Event::Link(n0, n1), Event::Link(n1, n2), Event::Visit(n2)]);
+        assert!(steps.maze().has_link(&n0, &n1));
+    }
+
+    #[test]
+    fn recursive_backtracker_should_visit_then_carve_then_backtrack() {
+        let events: Vec<Event> = RecursiveBacktracker::new(4, 4, StdRng::seed_from_u64(2)).unwrap().collect();
+        assert!(matches!(events[0], Event::Visit(_)));
+        assert!(matches!(events[events.len() - 1], Event::Backtrack(_)));
+        let links = events.iter().filter(|e| matches!(e, Event::Link(..))).count();
+        let backtracks = events.iter().filter(|e| matches!(e, Event::Backtrack(_))).count();
+        // a perfect maze has one less passage than it has nodes, and every node is backtracked
+        assert_eq!(links, 15);
+        assert_eq!(backtracks, 16);
+    }
+
+    #[test]
+    fn hunt_kill_should_emit_hunt_scans() {
+        let events: Vec<Event> = HuntKill::new(5, 5, StdRng::seed_from_u64(3)).unwrap().collect();
+        assert!(events.iter().any(|e| matches!(e, Event::HuntScan(_))));
+    }
+
+    #[test]
+    fn hunt_kill_should_start_hunts_at_the_first_unvisited_node() {
+        let mut visited = [false; 100];
+        for event in HuntKill::new(10, 10, StdRng::seed_from_u64(3)).unwrap() {
+            match event {
+                Event::Link(node1, node2) => {
+                    visited[node1.pos()] = true;
+                    visited[node2.pos()] = true;
+                }
+                Event::HuntScan(node) => {
+                    let first_unvisited = visited.iter().position(|v| !v).unwrap();
+                    assert!(node.pos() >= first_unvisited, "rescanned visited node {}", node.pos());
+                }
+                _ => (),
+            }
+        }
+        assert!(visited.iter().all(|v| *v));
+    }
+
+    #[test]
+    fn sidewinder_should_carve_north_from_any_node_of_a_run() {
+        let maze = Sidewinder::new(10, 10, StdRng::seed_from_u64(5)).unwrap().finish();
+        // when a run is closed out, the node that closed it doesn't always carve north, so below
+        // the top row there are nodes that carve neither east nor north
+        let carves_nothing = maze.iter_nodes()
+            .filter(|node| maze.north(node).is_some())
+            .filter(|node| {
+                let carved = |nbr: Option<_>| nbr.is_some_and(|nbr| maze.has_link(node, &nbr));
+                !carved(maze.north(node)) && !carved(maze.east(node))
+            })
+            .count();
+        assert!(carves_nothing > 0);
+        assert!(maze.is_perfect());
+    }
+
+    #[test]
+    fn draining_steps_should_produce_same_maze_as_finish() {
+        let mut steps = RecursiveBacktracker::new(6, 6, StdRng::seed_from_u64(4)).unwrap();
+        steps.by_ref().for_each(drop);
+        let drained = steps.into_maze();
+        let finished = RecursiveBacktracker::new(6, 6, StdRng::seed_from_u64(4)).unwrap().finish();
+        assert_eq!(drained.to_string(), finished.to_string());
+    }
+
+    #[test]
+    fn tiled_mazes_should_be_perfect() {
+        for algo in Algorithm::ALL.iter() {
+            // 4x5 tiles don't divide the maze, so the south and east tiles are smaller
+            let maze = tiled::generate(*algo, 18, 23, 4, 5, 7).unwrap();
+            assert!(maze.is_perfect(), "{}", algo);
+        }
+        // a single tile, and tiles of a single node
+        assert!(tiled::generate(Algorithm::Wilsons, 6, 6, 10, 10, 1).unwrap().is_perfect());
+        assert!(tiled::generate(Algorithm::Wilsons, 6, 6, 1, 1, 1).unwrap().is_perfect());
+    }
+
+    #[test]
+    fn tiled_mazes_should_not_depend_on_the_number_of_threads() {
+        let generate_on = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            pool.install(|| tiled::generate(Algorithm::RecursiveBacktracker, 40, 40, 8, 8, 3))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(generate_on(1), generate_on(4));
+        assert_ne!(
+            generate_on(4),
+            tiled::generate(Algorithm::RecursiveBacktracker, 40, 40, 8, 8, 4).unwrap().to_string()
+        );
+    }
+
+    #[test]
+    fn tiled_should_reject_tiles_without_nodes() {
+        let err = tiled::generate(Algorithm::Prims, 10, 10, 0, 5, 1).unwrap_err();
+        assert_eq!(err, MazeError::ZeroDimension { rows: 0, cols: 5 });
+    }
+
+    #[test]
+    fn generators_should_reject_mazes_without_nodes() {
+        for algo in Algorithm::ALL.iter() {
+            let err = algo.generate_with_rng(0, 4, StdRng::seed_from_u64(1)).unwrap_err();
+            assert_eq!(err, MazeError::ZeroDimension { rows: 0, cols: 4 }, "{}", algo);
+            assert!(algo.steps(4, 0, StdRng::seed_from_u64(1)).is_err(), "{}", algo);
+        }
+    }
+
+    #[test]
+    fn should_parse_algorithm_names() {
+        for algo in Algorithm::ALL.iter() {
+            assert_eq!(algo.name().parse::<Algorithm>(), Ok(*algo));
+        }
+        assert_eq!("hunt-kill".parse::<Algorithm>(), Ok(Algorithm::HuntKill));
+        assert!("kruskals".parse::<Algorithm>().is_err());
+    }
+}
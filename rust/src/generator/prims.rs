@@ -1,5 +1,8 @@
+use arrayvec::ArrayVec;
 use rand::{thread_rng, Rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
 use crate::maze::grid_node::GridNode;
 
 /// Generates a random maze using Prims algorithm.
@@ -21,46 +24,91 @@ use crate::maze::grid_node::GridNode;
 /// `height` - the number of rows to generate
 /// `width` - the number of columns to generate
 /// # Returns
-/// a `GridMaze` containing the randomly generated maze
+/// a `GridMaze` containing the randomly generated maze, or a `MazeError` if `height` or `width`
+/// is 0
 ///
-pub fn generate(height: usize, width: usize) -> GridMaze {
-    let mut maze = GridMaze::new(height, width);
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(Prims::new(height, width, thread_rng())?.finish())
+}
+
+/// Step-wise version of Prim's algorithm, see [`generate`] for a description of the algorithm.
+/// Each step either links the lowest weighted node to one of its neighbors, returning
+/// `Event::Link`, or removes a node without unlinked neighbors from the set of nodes to visit,
+/// returning `Event::Backtrack`
+pub struct Prims<R> {
+    maze: GridMaze,
+    rng: R,
+    // holds the nodes to be visited, `None` until the first step is taken
+    to_visit: Option<Vec<GridNode>>,
+}
+
+impl<R: Rng> Prims<R> {
+    /// returns a new Prim's generator for a maze of `height` rows and `width` columns,
+    /// using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, mut rng: R) -> Result<Self, MazeError> {
+        let mut maze = GridMaze::new(height, width)?;
 
-    // assign random weights to all cells in the maze
-    for node in maze.iter_mut_nodes() {
-        node.set_weight(thread_rng().gen_range(1, 101));
+        // assign random weights to all cells in the maze
+        for node in maze.iter_mut_nodes() {
+            node.set_weight(rng.gen_range(1, 101));
+        }
+
+        Ok(Self {
+            maze,
+            rng,
+            to_visit: None,
+        })
     }
+}
+
+impl<R: Rng> Iterator for Prims<R> {
+    type Item = Event;
 
-    // holds the nodes to be visited
-    let mut to_visit = vec![maze.random_node()];
+    fn next(&mut self) -> Option<Self::Item> {
+        let to_visit = match self.to_visit.as_mut() {
+            Some(to_visit) => to_visit,
+            None => {
+                let start = self.maze.random_node_with(&mut self.rng);
+                self.to_visit = Some(vec![start]);
+                return Some(Event::Visit(start));
+            }
+        };
+
+        if to_visit.is_empty() {
+            return None;
+        }
 
-    while !to_visit.is_empty() {
         // sort the to_visit nodes by weight
         to_visit.sort_by_key(|node| node.weight());
         let cur_node = to_visit[0];
 
-        let mut neighbors = unlinked_neighbors(&maze, &cur_node);
+        let mut neighbors = unlinked_neighbors(&self.maze, &cur_node);
 
         if !neighbors.is_empty() {
             neighbors.sort_by_key(|node| node.weight());
             // link cur_node to the lowest weighted neighbor node
-            maze.link(&cur_node, &neighbors[0], true);
+            self.maze.link(&cur_node, &neighbors[0], true);
             to_visit.push(neighbors[0]);
+            Some(Event::Link(cur_node, neighbors[0]))
         } else {
             // remove cur_node from to_visit, it should always be the first element of to_visit
             to_visit.remove(0);
+            Some(Event::Backtrack(cur_node))
         }
     }
+}
 
-    maze
+impl<R: Rng> Steps for Prims<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
+    }
+
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
 }
 
-/// returns a vector of nodes that are unlinked neighbors of the given `node`
-fn unlinked_neighbors(maze: &GridMaze, node: &GridNode) -> Vec<GridNode> {
-    maze
-        .neighbors(node)
-        .iter()
-        .filter(|&neighbor| maze.get_links(neighbor).is_empty())
-        .copied()
-        .collect()
-}
\ No newline at end of file
+/// returns the nodes that are unlinked neighbors of the given `node`
+fn unlinked_neighbors(maze: &GridMaze, node: &GridNode) -> ArrayVec<GridNode, 4> {
+    maze.unlinked_neighbors_iter(node).collect()
+}
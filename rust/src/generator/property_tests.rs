@@ -0,0 +1,54 @@
+//! Property based tests that every generator makes a perfect maze, for any size and seed.
+
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use crate::generator::Algorithm;
+use crate::maze::grid_maze::GridMaze;
+
+/// returns the number of passages in the maze, a bi-directional link counts as one passage
+fn passage_count(maze: &GridMaze) -> usize {
+    maze.iter_nodes().map(|node| maze.get_links(node).len()).sum::<usize>() / 2
+}
+
+fn algorithm() -> impl Strategy<Value = Algorithm> {
+    prop::sample::select(Algorithm::ALL.to_vec())
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn every_generator_should_make_a_perfect_maze(
+        algo in algorithm(),
+        rows in 1usize..=40,
+        cols in 1usize..=40,
+        seed in any::<u64>(),
+    ) {
+        let maze = algo.generate_with_rng(rows, cols, StdRng::seed_from_u64(seed)).unwrap();
+        prop_assert_eq!(maze.dimensions(), (rows, cols));
+
+        let validation = maze.validate();
+        prop_assert!(validation.is_valid(), "{}", validation);
+        prop_assert!(validation.unreachable.is_empty(), "{}", validation);
+        prop_assert_eq!(validation.cycles, 0);
+        // a spanning tree has one less passage than it has nodes
+        prop_assert_eq!(passage_count(&maze), rows * cols - 1);
+    }
+
+    #[test]
+    fn single_row_and_column_mazes_should_be_corridors(
+        algo in algorithm(),
+        len in 1usize..=100,
+        seed in any::<u64>(),
+    ) {
+        for (rows, cols) in [(1, len), (len, 1)] {
+            let maze = algo.generate_with_rng(rows, cols, StdRng::seed_from_u64(seed)).unwrap();
+            prop_assert!(maze.is_perfect());
+            // the only spanning tree of a single row or column links every node to the next
+            for pos in 1..len {
+                prop_assert!(maze.has_link(&maze[pos - 1], &maze[pos]));
+            }
+        }
+    }
+}
@@ -1,6 +1,9 @@
+use arrayvec::ArrayVec;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
 use crate::maze::grid_node::GridNode;
 
 /// Returns a maze generated using the recursive-backtracker algorithm
@@ -17,38 +20,78 @@ use crate::maze::grid_node::GridNode;
 /// in essence, this carves passages using a depth-first search with back-tracking.
 /// Also, like hunt-and-kill, recursive-backtracker also produces mazed that are full of long
 /// and meandering passages.
-pub fn generate(height: usize, width: usize) -> GridMaze {
-    let mut maze = GridMaze::new(height, width);
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(RecursiveBacktracker::new(height, width, thread_rng())?.finish())
+}
 
-    // pick a random position to start at
-    let start = maze.random_node();
+/// Step-wise version of the recursive-backtracker algorithm, see [`generate`] for a description
+/// of the algorithm. Carving into a neighbor returns `Event::Link`, and popping a node off of the
+/// stack returns `Event::Backtrack`
+pub struct RecursiveBacktracker<R> {
+    maze: GridMaze,
+    rng: R,
+    // the stack of visited maze positions, `None` until the first step is taken
+    stack: Option<Vec<GridNode>>,
+}
 
-    // the stack of visited maze positions
-    let mut stack = vec![start];
+impl<R: Rng> RecursiveBacktracker<R> {
+    /// returns a new recursive-backtracker generator for a maze of `height` rows and `width`
+    /// columns, using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, rng: R) -> Result<Self, MazeError> {
+        Ok(Self {
+            maze: GridMaze::new(height, width)?,
+            rng,
+            stack: None,
+        })
+    }
+}
+
+impl<R: Rng> Iterator for RecursiveBacktracker<R> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let stack = match self.stack.as_mut() {
+            Some(stack) => stack,
+            None => {
+                // pick a random position to start at
+                let start = self.maze.random_node_with(&mut self.rng);
+                self.stack = Some(vec![start]);
+                return Some(Event::Visit(start));
+            }
+        };
+
+        // while we have positions left to visit...
+        let current_node = *stack.last()?;
 
-    // while we have positions left to visit...
-    while let Some(current_node) = stack.last() {
         // get neighbors of the current node that are not linked to other nodes (unvisited)
-        let unlinked_neighbors = maze
-            .neighbors(current_node)
-            .iter()
-            .filter(|&node| maze.get_links(node).is_empty())
-            .copied()
-            .collect::<Vec<GridNode>>();
+        let unlinked_neighbors = self.maze
+            .unlinked_neighbors_iter(&current_node)
+            .collect::<ArrayVec<GridNode, 4>>();
 
         // if there are unvisited neighbors choose a random neighbor, link to it, and push it
         // onto the stack.
-        if !unlinked_neighbors.is_empty() {
-            if let Some(rand_neighbor) = unlinked_neighbors.choose(&mut thread_rng()) {
-                maze.link(current_node, rand_neighbor, true);
+        match unlinked_neighbors.choose(&mut self.rng) {
+            Some(rand_neighbor) => {
+                self.maze.link(&current_node, rand_neighbor, true);
                 stack.push(*rand_neighbor);
+                Some(Event::Link(current_node, *rand_neighbor))
+            }
+            None => {
+                // else every neighbor has been visited, so backtrack by popping the current pos
+                // off of the stack
+                stack.pop();
+                Some(Event::Backtrack(current_node))
             }
-        } else {
-            // else every neighbor has been visited, so backtrack by popping the current pos
-            // off of the stack
-            stack.pop();
         }
     }
+}
+
+impl<R: Rng> Steps for RecursiveBacktracker<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
+    }
 
-    maze
-}
\ No newline at end of file
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
+}
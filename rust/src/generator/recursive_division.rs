@@ -1,5 +1,9 @@
+use std::collections::VecDeque;
 use rand::{Rng, thread_rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
+use crate::maze::grid_node::GridNode;
 
 /// The Recursive Division algorithm is unique among the other algorithms implemented
 /// here, for two reasons. First of all, it treats the maze as a fractal, a shape whose
@@ -10,74 +14,146 @@ use crate::maze::grid_maze::GridMaze;
 /// It works by dividing the grid into two subgrids, adding a wall between them
 /// and a single passage linking them. The algorithm is then repeated on each
 /// side, recursively, until the passages are the desired size.
-pub fn generate(height: usize, width: usize) -> GridMaze {
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(RecursiveDivision::new(height, width, thread_rng())?.finish())
+}
 
-    let mut maze = GridMaze::new(height, width);
+/// a rectangular region of the maze that still needs to be divided
+#[derive(Debug, Copy, Clone)]
+struct Region {
+    row: usize,
+    col: usize,
+    height: usize,
+    width: usize,
+}
 
-    // initially link each node of the maze to all its neighbors
-    for pos in 0..maze.len() {
-        let node = maze[pos];
-        println!("node:{}", &node.pos());
-        for nbr in maze.neighbors(&node) {
-            println!("   nbr:{:?}", &nbr.pos());
-            maze.link(&node, &nbr, false);
-        }
-    }
+/// Step-wise version of the Recursive Division algorithm, see [`generate`] for a description of
+/// the algorithm. The maze starts out with every node linked to its neighbors, then each step
+/// adds a single wall segment and returns it as an `Event::Unlink`.
+///
+/// The recursion of the original algorithm is replaced by an explicit stack of regions, which
+/// are divided in the same (depth-first) order.
+pub struct RecursiveDivision<R> {
+    maze: GridMaze,
+    rng: R,
+    // regions left to divide, the region on top of the stack is divided next
+    regions: Vec<Region>,
+    // wall segments of the current division that have not been added yet
+    walls: VecDeque<(GridNode, GridNode)>,
+}
 
-    divide(&mut maze, 0, 0, height, width);
+impl<R: Rng> RecursiveDivision<R> {
+    /// returns a new Recursive Division generator for a maze of `height` rows and `width` columns,
+    /// using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, rng: R) -> Result<Self, MazeError> {
+        let mut maze = GridMaze::new(height, width)?;
 
-    maze
-}
+        // initially link each node of the maze to all its neighbors
+        for pos in 0..maze.len() {
+            let node = maze[pos];
+            for nbr in maze.neighbors_iter(&node) {
+                maze.link(&node, &nbr, false);
+            }
+        }
 
-fn divide(maze: &mut GridMaze, row: usize, col: usize, height: usize, width: usize) {
-    //println!("r:{row} c:{col} h:{height} w:{width} \n{maze}");
-    if height <= 1 || width <= 1 {
-        return
+        Ok(Self {
+            maze,
+            rng,
+            regions: vec![Region { row: 0, col: 0, height, width }],
+            walls: VecDeque::new(),
+        })
     }
-    if height > width {
-        divide_horizontally(maze, row, col, height, width);
-    } else {
-        divide_vertically(maze, row, col, height, width);
+
+    /// divides the `region` in two, queueing the wall segments between the two halves and
+    /// pushing both halves onto the region stack. The region must be at least 2x2, smaller
+    /// regions can't be divided
+    fn divide(&mut self, region: Region) {
+        if region.height > region.width {
+            self.divide_horizontally(region);
+        } else {
+            self.divide_vertically(region);
+        }
     }
-}
 
-fn divide_horizontally(maze: &mut GridMaze, row: usize, col: usize, height: usize, width: usize) {
-    let divide_south_of = thread_rng().gen_range(0, height-1);
-    let passage_at = thread_rng().gen_range(0, width);
+    fn divide_horizontally(&mut self, region: Region) {
+        let Region { row, col, height, width } = region;
+        let divide_south_of = self.rng.gen_range(0, height - 1);
+        let passage_at = self.rng.gen_range(0, width);
 
-    for x in 0..width {
-        if passage_at == x {
-            continue
-        }
+        for x in 0..width {
+            if passage_at == x {
+                continue
+            }
 
-        if let Some(node) = maze.get2d(row + divide_south_of, col + x) {
-            if let Some(south_node) = maze.south(&node) {
-                maze.unlink(&node, &south_node);
+            if let Some(node) = self.maze.get2d(row + divide_south_of, col + x) {
+                if let Some(south_node) = self.maze.south(&node) {
+                    self.walls.push_back((node, south_node));
+                }
             }
         }
+
+        // push the second half first, so that the first half is divided first
+        self.regions.push(Region {
+            row: row + divide_south_of + 1,
+            col,
+            height: height - divide_south_of - 1,
+            width,
+        });
+        self.regions.push(Region { row, col, height: divide_south_of + 1, width });
     }
 
-    divide(maze, row, col, divide_south_of + 1, width);
-    divide(maze, row + divide_south_of + 1, col, height - divide_south_of - 1, width);
-}
+    fn divide_vertically(&mut self, region: Region) {
+        let Region { row, col, height, width } = region;
+        let divide_east_of = self.rng.gen_range(0, width - 1);
+        let passage_at = self.rng.gen_range(0, height);
 
-fn divide_vertically(maze: &mut GridMaze, row: usize, col: usize, height: usize, width: usize) {
-    let divide_east_of = thread_rng().gen_range(0, width-1);
-    let passage_at = thread_rng().gen_range(0, height);
+        for y in 0..height {
+            if passage_at == y {
+                continue
+            }
 
-    for y in 0..height {
-        if passage_at == y {
-            continue
+            if let Some(node) = self.maze.get2d(row + y, col + divide_east_of) {
+                if let Some(east_node) = self.maze.east(&node) {
+                    self.walls.push_back((node, east_node));
+                }
+            }
         }
 
-        if let Some(node) = maze.get2d(row + y, col + divide_east_of) {
+        // push the second half first, so that the first half is divided first
+        self.regions.push(Region {
+            row,
+            col: col + divide_east_of + 1,
+            height,
+            width: width - divide_east_of - 1,
+        });
+        self.regions.push(Region { row, col, height, width: divide_east_of + 1 });
+    }
+}
+
+impl<R: Rng> Iterator for RecursiveDivision<R> {
+    type Item = Event;
 
-            if let Some(east_node) = maze.east(&node) {
-                maze.unlink(&node, &east_node);
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((node1, node2)) = self.walls.pop_front() {
+                self.maze.unlink(&node1, &node2);
+                return Some(Event::Unlink(node1, node2));
+            }
+
+            let region = self.regions.pop()?;
+            if region.height > 1 && region.width > 1 {
+                self.divide(region);
             }
         }
     }
+}
+
+impl<R: Rng> Steps for RecursiveDivision<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
+    }
 
-    divide(maze, row, col, height, divide_east_of + 1);
-    divide(maze, row, col + divide_east_of + 1, height, width - divide_east_of - 1);
-}
\ No newline at end of file
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
+}
@@ -1,7 +1,9 @@
 
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
 use crate::maze::grid_node::GridNode;
 
 /// Generates a random maze using the Sidewinder algorithm. It's similar to binary tree but
@@ -14,38 +16,84 @@ use crate::maze::grid_node::GridNode;
 ///     passage north. Then empty the run set, set the next cell in the row to be the current
 ///     cell, and repeat steps 2-5.
 ///  6. Continue until all rows have been processed.
-pub fn generate(height: usize, width: usize) -> GridMaze {
-    let at_eastern_boundary = |maze: &GridMaze, cell: &GridNode| maze.east(cell).is_none();
-    let at_northern_boundary = |maze: &GridMaze, cell: &GridNode| maze.north(cell).is_none();
-    // should we close out the current run of cells
-    let should_close_out = |maze: &GridMaze, cell: &GridNode| {
-        at_eastern_boundary(maze, cell) || (!at_northern_boundary(maze, cell) && thread_rng().gen::<bool>())
-    };
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(Sidewinder::new(height, width, thread_rng())?.finish())
+}
+
+/// Step-wise version of the Sidewinder algorithm, see [`generate`] for a description of the
+/// algorithm. Each step processes one node and returns the `Event::Link` it carved, or
+/// `Event::Visit` if nothing could be carved
+pub struct Sidewinder<R> {
+    maze: GridMaze,
+    rng: R,
+    // index of the next node to process
+    cur_index: usize,
+    // the nodes of the current run, one of them is linked north when the run is closed out
+    run: Vec<GridNode>,
+}
+
+impl<R: Rng> Sidewinder<R> {
+    /// returns a new Sidewinder generator for a maze of `height` rows and `width` columns,
+    /// using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, rng: R) -> Result<Self, MazeError> {
+        Ok(Self {
+            maze: GridMaze::new(height, width)?,
+            rng,
+            cur_index: 0,
+            run: Vec::new(),
+        })
+    }
 
-    let mut maze = GridMaze::new(height, width);
+    /// should we close out the current run of cells
+    fn should_close_out(&mut self, cell: &GridNode) -> bool {
+        let at_eastern_boundary = self.maze.east(cell).is_none();
+        let at_northern_boundary = self.maze.north(cell).is_none();
+        at_eastern_boundary || (!at_northern_boundary && self.rng.gen::<bool>())
+    }
+}
 
-    for cur_index in 0..maze.len() {
-        let cur_node = maze[cur_index];
-        let mut runs = vec![cur_node];
+impl<R: Rng> Iterator for Sidewinder<R> {
+    type Item = Event;
 
-        if should_close_out(&maze, &cur_node) {
-            let rand_member = runs.choose(&mut thread_rng());
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cur_index >= self.maze.len() {
+            return None;
+        }
+
+        let cur_node = self.maze[self.cur_index];
+        self.cur_index += 1;
+        self.run.push(cur_node);
+
+        if self.should_close_out(&cur_node) {
+            let rand_member = self.run.choose(&mut self.rng).copied();
+            self.run.clear();
 
             // if the random_member has a north neighbor, carve a passage from the random cell
             // to it's north neighbor
             if let Some(rand_node) = rand_member {
-                if let Some(north_pos) = maze.north(rand_node) {
-                    maze.link(rand_node, &north_pos, true);
+                if let Some(north_pos) = self.maze.north(&rand_node) {
+                    self.maze.link(&rand_node, &north_pos, true);
+                    return Some(Event::Link(rand_node, north_pos));
                 }
             }
-            runs.clear();
         } else {
             // carve a passage from current cell to the east neighbor
-            if let Some(east_node) = maze.east(&cur_node) {
-                maze.link(&cur_node, &east_node, true);
+            if let Some(east_node) = self.maze.east(&cur_node) {
+                self.maze.link(&cur_node, &east_node, true);
+                return Some(Event::Link(cur_node, east_node));
             }
         }
+
+        Some(Event::Visit(cur_node))
     }
+}
 
-    maze
+impl<R: Rng> Steps for Sidewinder<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
+    }
+
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
 }
@@ -0,0 +1,166 @@
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use crate::generator::Algorithm;
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
+
+/// Generates a very large maze by splitting it into tiles, and generating the tiles in parallel.
+///
+/// 1. split the `height` x `width` maze into tiles of `tile_height` x `tile_width` nodes. The
+///    tiles along the south and east edges are smaller if the tile size doesn't divide the maze.
+/// 2. generate a perfect maze in every tile using `algorithm`. The tiles are generated in
+///    parallel, on rayon's global thread pool.
+/// 3. stitch the tiles together with Kruskal's algorithm: every pair of adjacent tiles is an
+///    edge of the "tile graph". The edges are visited in a random order, and whenever an edge
+///    joins two tiles that are not yet connected, one random passage is carved through the
+///    border between them.
+///
+/// Every tile is a spanning tree of its nodes, and step 3 carves a spanning tree of the tiles, so
+/// the result is a perfect maze. Passages only cross tile borders where the tiles were stitched,
+/// so a maze made of small tiles has visible seams.
+///
+/// Each tile gets its own RNG, seeded from `seed`, so the maze depends only on the arguments and
+/// not on the number of threads or the order the tiles were generated in.
+///
+/// Each tile is copied into the maze as soon as it is generated, so at most one tile per thread is
+/// held in memory alongside the maze.
+///
+/// # Returns
+/// the generated maze, or a `MazeError` if the maze can't be created or if `tile_height` or
+/// `tile_width` is 0
+pub fn generate(
+    algorithm: Algorithm,
+    height: usize,
+    width: usize,
+    tile_height: usize,
+    tile_width: usize,
+    seed: u64,
+) -> Result<GridMaze, MazeError> {
+    let mut maze = GridMaze::new(height, width)?;
+    if tile_height == 0 || tile_width == 0 {
+        return Err(MazeError::ZeroDimension { rows: tile_height, cols: tile_width });
+    }
+
+    let tile_rows = height.div_ceil(tile_height);
+    let tile_cols = width.div_ceil(tile_width);
+    let tiles: Vec<Tile> = (0..tile_rows * tile_cols)
+        .map(|idx| {
+            let (row, col) = (idx / tile_cols * tile_height, idx % tile_cols * tile_width);
+            Tile {
+                row,
+                col,
+                height: tile_height.min(height - row),
+                width: tile_width.min(width - col),
+            }
+        })
+        .collect();
+
+    // the seeds are drawn up front, in tile order, so that no tile depends on another tile's RNG
+    let mut rng = StdRng::seed_from_u64(seed);
+    let seeds: Vec<u64> = tiles.iter().map(|_| rng.gen()).collect();
+
+    // every tile is copied into the maze as soon as it has been generated, and then freed, so
+    // only the tiles being generated are held in memory alongside the maze. A link never leaves
+    // its tile, so a tile's link bits can be copied into the maze unchanged. Each tile row of the
+    // maze is split into the disjoint pieces of its rows covered by each tile
+    maze.link_bits_mut()
+        .par_chunks_mut(tile_height * width)
+        .zip(tiles.par_chunks(tile_cols))
+        .zip(seeds.par_chunks(tile_cols))
+        .try_for_each(|((rows, row_tiles), row_seeds)| {
+            let mut pieces: Vec<Vec<&mut [u8]>> = row_tiles.iter().map(|_| Vec::new()).collect();
+            for row in rows.chunks_mut(width) {
+                let mut rest = row;
+                for (tile, tile_pieces) in row_tiles.iter().zip(pieces.iter_mut()) {
+                    let (piece, tail) = rest.split_at_mut(tile.width);
+                    tile_pieces.push(piece);
+                    rest = tail;
+                }
+            }
+
+            row_tiles
+                .par_iter()
+                .zip(row_seeds.par_iter())
+                .zip(pieces.into_par_iter())
+                .try_for_each(|((tile, tile_seed), tile_pieces)| {
+                    let tile_maze = algorithm.generate_with_rng(
+                        tile.height,
+                        tile.width,
+                        StdRng::seed_from_u64(*tile_seed),
+                    )?;
+                    let tile_rows = tile_maze.link_bits().chunks(tile.width);
+                    for (piece, bits) in tile_pieces.into_iter().zip(tile_rows) {
+                        piece.copy_from_slice(bits);
+                    }
+                    Ok(())
+                })
+        })?;
+
+    // the borders between adjacent tiles, as (tile index, tile index) pairs, where the second
+    // tile is either east or south of the first
+    let mut borders: Vec<(usize, usize)> = Vec::with_capacity(2 * tiles.len());
+    for idx in 0..tiles.len() {
+        if (idx + 1) % tile_cols != 0 {
+            borders.push((idx, idx + 1));
+        }
+        if idx + tile_cols < tiles.len() {
+            borders.push((idx, idx + tile_cols));
+        }
+    }
+    borders.shuffle(&mut rng);
+
+    // Kruskal's algorithm over the tiles, `sets` holds the parent of each tile's set
+    let mut sets: Vec<usize> = (0..tiles.len()).collect();
+    for (idx1, idx2) in borders {
+        let (set1, set2) = (find(&mut sets, idx1), find(&mut sets, idx2));
+        if set1 == set2 {
+            continue;
+        }
+        sets[set1] = set2;
+
+        let (tile1, tile2) = (&tiles[idx1], &tiles[idx2]);
+        let (node1, node2) = if tile1.row == tile2.row {
+            // tile2 is east of tile1, carve through a random row of their shared border
+            let row = tile1.row + rng.gen_range(0, tile1.height);
+            (
+                maze[GridMaze::idx_1d(row, tile2.col - 1, width)],
+                maze[GridMaze::idx_1d(row, tile2.col, width)],
+            )
+        } else {
+            // tile2 is south of tile1, carve through a random column of their shared border
+            let col = tile1.col + rng.gen_range(0, tile1.width);
+            (
+                maze[GridMaze::idx_1d(tile2.row - 1, col, width)],
+                maze[GridMaze::idx_1d(tile2.row, col, width)],
+            )
+        };
+        maze.link(&node1, &node2, true);
+    }
+
+    Ok(maze)
+}
+
+/// the rectangle of maze nodes that a tile covers
+struct Tile {
+    row: usize,
+    col: usize,
+    height: usize,
+    width: usize,
+}
+
+/// returns the representative of the set containing `idx`, compressing the path to it
+fn find(sets: &mut [usize], idx: usize) -> usize {
+    let mut root = idx;
+    while sets[root] != root {
+        root = sets[root];
+    }
+    let mut cur = idx;
+    while sets[cur] != root {
+        let next = sets[cur];
+        sets[cur] = root;
+        cur = next;
+    }
+    root
+}
@@ -0,0 +1,135 @@
+//! Statistical tests of the distribution of the mazes made by the generators.
+//!
+//! A perfect maze is a spanning tree of the grid. Aldous-Broder and Wilson's pick every spanning
+//! tree with the same probability, while Binary Tree and Sidewinder can only make some of them.
+//! Every spanning tree of a small grid is enumerated, many seeded mazes are generated and counted
+//! by tree, and a chi-square test checks the counts against the uniform distribution.
+
+use std::collections::HashMap;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use crate::generator::Algorithm;
+use crate::maze::grid_maze::GridMaze;
+
+/// the number of mazes generated for every spanning tree of the grid, so that the expected count
+/// of each tree is large enough for the chi-square test to be accurate
+const SAMPLES_PER_TREE: usize = 40;
+
+/// the z-score above which the counts are considered to not be uniform, this is a significance
+/// level of 0.001
+const CRITICAL_Z: f64 = 3.09;
+
+/// returns the passages between adjacent nodes of a `rows` x `cols` grid, as pairs of positions
+fn grid_edges(rows: usize, cols: usize) -> Vec<(usize, usize)> {
+    let mut edges = vec![];
+    for row in 0..rows {
+        for col in 0..cols {
+            let pos = GridMaze::idx_1d(row, col, cols);
+            if col + 1 < cols {
+                edges.push((pos, pos + 1));
+            }
+            if row + 1 < rows {
+                edges.push((pos, pos + cols));
+            }
+        }
+    }
+    edges
+}
+
+/// returns `true` if the edges selected by `mask` connect all `nodes` without a cycle
+fn is_spanning_tree(nodes: usize, edges: &[(usize, usize)], mask: u32) -> bool {
+    let mut parents: Vec<usize> = (0..nodes).collect();
+    let find = |parents: &mut Vec<usize>, mut pos: usize| {
+        while parents[pos] != pos {
+            pos = parents[pos];
+        }
+        pos
+    };
+    for (i, (pos1, pos2)) in edges.iter().enumerate() {
+        if mask & (1 << i) != 0 {
+            let (root1, root2) = (find(&mut parents, *pos1), find(&mut parents, *pos2));
+            if root1 == root2 {
+                return false;
+            }
+            parents[root1] = root2;
+        }
+    }
+    true
+}
+
+/// returns every spanning tree of the grid, as a bit mask over `edges`
+fn spanning_trees(nodes: usize, edges: &[(usize, usize)]) -> Vec<u32> {
+    (0..1u32 << edges.len())
+        .filter(|mask| mask.count_ones() as usize == nodes - 1)
+        .filter(|mask| is_spanning_tree(nodes, edges, *mask))
+        .collect()
+}
+
+/// returns the bit mask of the `edges` that are passages of the `maze`
+fn tree_of(maze: &GridMaze, edges: &[(usize, usize)]) -> u32 {
+    edges.iter()
+        .enumerate()
+        .filter(|(_i, (pos1, pos2))| maze.has_link(&maze[*pos1], &maze[*pos2]))
+        .fold(0, |mask, (i, _edge)| mask | (1 << i))
+}
+
+/// generates mazes with `algo` and returns the chi-square statistic of their counts, per spanning
+/// tree, against the uniform distribution, along with the number of distinct trees that were made
+fn chi_square(algo: Algorithm, rows: usize, cols: usize) -> (f64, usize, usize) {
+    let edges = grid_edges(rows, cols);
+    let trees = spanning_trees(rows * cols, &edges);
+    let samples = trees.len() * SAMPLES_PER_TREE;
+
+    let mut counts: HashMap<u32, usize> = trees.iter().map(|tree| (*tree, 0)).collect();
+    for seed in 0..samples {
+        let maze = algo.generate_with_rng(rows, cols, StdRng::seed_from_u64(seed as u64)).unwrap();
+        let tree = tree_of(&maze, &edges);
+        *counts.get_mut(&tree).expect("maze should be a spanning tree of the grid") += 1;
+    }
+
+    let expected = SAMPLES_PER_TREE as f64;
+    let statistic = counts.values()
+        .map(|count| (*count as f64 - expected).powi(2) / expected)
+        .sum();
+    let seen = counts.values().filter(|count| **count > 0).count();
+    (statistic, trees.len() - 1, seen)
+}
+
+/// converts a chi-square `statistic` with `df` degrees of freedom to a standard normal z-score,
+/// using the Wilson-Hilferty approximation
+fn wilson_hilferty(statistic: f64, df: usize) -> f64 {
+    let k = df as f64;
+    let variance = 2.0 / (9.0 * k);
+    ((statistic / k).cbrt() - (1.0 - variance)) / variance.sqrt()
+}
+
+#[test]
+fn should_count_spanning_trees_of_small_grids() {
+    assert_eq!(spanning_trees(4, &grid_edges(2, 2)).len(), 4);
+    assert_eq!(spanning_trees(6, &grid_edges(2, 3)).len(), 15);
+    assert_eq!(spanning_trees(9, &grid_edges(3, 3)).len(), 192);
+}
+
+#[test]
+fn random_walk_generators_should_be_uniform() {
+    for algo in [Algorithm::AldousBroder, Algorithm::Wilsons].iter() {
+        for (rows, cols) in [(2, 2), (2, 3), (3, 3)].iter() {
+            let (statistic, df, seen) = chi_square(*algo, *rows, *cols);
+            let z = wilson_hilferty(statistic, df);
+            assert_eq!(seen, df + 1, "{} {}x{} should make every tree", algo, rows, cols);
+            assert!(z < CRITICAL_Z, "{} {}x{}: chi-square {:.1}, z {:.2}", algo, rows, cols, statistic, z);
+        }
+    }
+}
+
+#[test]
+fn binary_tree_and_sidewinder_should_be_biased() {
+    for algo in [Algorithm::BinaryTree, Algorithm::Sidewinder].iter() {
+        for (rows, cols) in [(2, 3), (3, 3)].iter() {
+            let (statistic, df, seen) = chi_square(*algo, *rows, *cols);
+            let z = wilson_hilferty(statistic, df);
+            assert!(seen < df + 1, "{} {}x{} should not make every tree", algo, rows, cols);
+            assert!(z > CRITICAL_Z, "{} {}x{}: chi-square {:.1}, z {:.2}", algo, rows, cols, statistic, z);
+        }
+    }
+}
@@ -1,6 +1,10 @@
+use arrayvec::ArrayVec;
+use std::collections::VecDeque;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
+use rand::{thread_rng, Rng};
+use crate::generator::{Event, Steps};
 use crate::maze::grid_maze::GridMaze;
+use crate::maze::error::MazeError;
 use crate::maze::grid_node::GridNode;
 
 /// Generates a random maze using Wilson's algorithm:
@@ -14,53 +18,163 @@ use crate::maze::grid_node::GridNode;
 ///    reach a visited node.
 /// 3. link all the nodes in the current random walk to the visited node
 /// 4. repeat step 2 until all nodes in the maze have been visited
-pub fn generate(height: usize, width: usize) -> GridMaze {
-    let mut maze = GridMaze::new(height, width);
-
-    // choose a random node in the maze, this will be the first visited node
-    let first = maze.random_node();
-    // initialize unvisited to contain all positions in the maze except for first
-    let mut unvisited_nodes: Vec<GridNode> = maze
-        .iter_nodes()
-        .filter(|&node| *node != first)
-        .copied()
-        .collect();
-
-    // repeat until all nodes have been visited
-    while !unvisited_nodes.is_empty() {
-        // choose a random, unvisited node and add it to the `path` that is about to be walked
-        let mut cur_node = *unvisited_nodes.choose(&mut thread_rng()).unwrap();
-        // path contains the randomly walked nodes
-        let mut path: Vec<GridNode> = vec![cur_node];
-
-        // while the cur_node is a member of unvisited nodes
-        while unvisited_nodes.contains(&cur_node) {
-            // choose a random neighbor of the current node
-            cur_node = *maze
-                .neighbors(&cur_node)
-                .choose(&mut thread_rng())
-                .expect("all nodes will have at least two neighbors");
-
-            // if the random neighbor is already in path, there is a loop, so remove it
-            if let Some(node_index) = path.iter().position(|node| *node == cur_node) {
-                path = path[0..=node_index].to_vec();
-            } else {
-                // the random neigbor is not going to make a loop, so push it onto the path
-                path.push(cur_node);
-            }
+pub fn generate(height: usize, width: usize) -> Result<GridMaze, MazeError> {
+    Ok(Wilsons::new(height, width, thread_rng())?.finish())
+}
+
+/// Step-wise version of Wilson's algorithm, see [`generate`] for a description of the algorithm.
+/// Every node entered by the loop-erased random walk is returned as an `Event::Visit`. Once the
+/// walk reaches a visited node, its passages are carved one `Event::Link` at a time
+pub struct Wilsons<R> {
+    maze: GridMaze,
+    rng: R,
+    // unvisited nodes, `None` until the first step is taken
+    unvisited: Option<Unvisited>,
+    // the nodes of the random walk currently being performed
+    path: Vec<GridNode>,
+    // the index of every node within `path`, by node position, or NOT_IN_PATH
+    path_index: Vec<usize>,
+    // the links of a finished walk that have not been carved yet
+    carve: VecDeque<(GridNode, GridNode)>,
+}
+
+/// marks a node that is not part of the current random walk
+const NOT_IN_PATH: usize = usize::MAX;
+
+/// the set of unvisited nodes of a maze. Visited nodes are removed by swapping them with the last
+/// node of the set, so that removing and choosing a random node both take constant time
+struct Unvisited {
+    nodes: Vec<GridNode>,
+    // the index of every unvisited node within `nodes`, by node position
+    index: Vec<usize>,
+    // whether a node, by node position, has been visited
+    visited: Vec<bool>,
+}
+
+impl Unvisited {
+    /// returns a set containing every node of the `maze` except for `first`
+    fn new(maze: &GridMaze, first: GridNode) -> Self {
+        let mut unvisited = Self {
+            nodes: Vec::with_capacity(maze.len()),
+            index: vec![0; maze.len()],
+            visited: vec![false; maze.len()],
+        };
+        for node in maze.iter_nodes() {
+            unvisited.index[node.pos()] = unvisited.nodes.len();
+            unvisited.nodes.push(*node);
         }
+        unvisited.visit(&first);
+        unvisited
+    }
 
-        // carve passages (i.e. link) between the nodes in path
-        let mut window = path.windows(2);
-        while let Some([node1, node2]) = window.next() {
-            maze.link(node1, node2, true);
+    fn contains(&self, node: &GridNode) -> bool {
+        !self.visited[node.pos()]
+    }
 
-            // remove the nodes in the path from the vector of unvisited nodes
-            if let Some(path_idx) = unvisited_nodes.iter().position(|node| *node == *node1) {
-                unvisited_nodes.remove(path_idx);
+    /// removes `node` from the set, if it is still in it
+    fn visit(&mut self, node: &GridNode) {
+        if self.visited[node.pos()] {
+            return;
+        }
+        self.visited[node.pos()] = true;
+        let idx = self.index[node.pos()];
+        self.nodes.swap_remove(idx);
+        // the last node of the set was moved into the removed node's slot
+        if let Some(moved) = self.nodes.get(idx) {
+            self.index[moved.pos()] = idx;
+        }
+    }
+}
+
+impl<R: Rng> Wilsons<R> {
+    /// returns a new Wilson's generator for a maze of `height` rows and `width` columns,
+    /// using `rng` as its source of randomness
+    pub fn new(height: usize, width: usize, rng: R) -> Result<Self, MazeError> {
+        let maze = GridMaze::new(height, width)?;
+        let path_index = vec![NOT_IN_PATH; maze.len()];
+        Ok(Self {
+            maze,
+            rng,
+            unvisited: None,
+            path: vec![],
+            path_index,
+            carve: VecDeque::new(),
+        })
+    }
+}
+
+impl<R: Rng> Iterator for Wilsons<R> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let unvisited = match self.unvisited.as_mut() {
+            Some(unvisited) => unvisited,
+            None => {
+                // choose a random node in the maze, this will be the first visited node
+                let first = self.maze.random_node_with(&mut self.rng);
+                self.unvisited = Some(Unvisited::new(&self.maze, first));
+                return Some(Event::Visit(first));
+            }
+        };
+
+        loop {
+            // carve passages (i.e. link) between the nodes of a finished walk
+            if let Some((node1, node2)) = self.carve.pop_front() {
+                self.maze.link(&node1, &node2, true);
+                unvisited.visit(&node1);
+                return Some(Event::Link(node1, node2));
             }
+
+            let cur_node = match self.path.last() {
+                Some(cur_node) => *cur_node,
+                None => {
+                    // choose a random, unvisited node and add it to the `path` that is about to
+                    // be walked. If there are no unvisited nodes, the maze is complete
+                    let start = *unvisited.nodes.choose(&mut self.rng)?;
+                    self.path_index[start.pos()] = 0;
+                    self.path.push(start);
+                    return Some(Event::Visit(start));
+                }
+            };
+
+            if !unvisited.contains(&cur_node) {
+                // the walk reached a visited node, queue up the links between the nodes in path
+                self.carve.extend(self.path.windows(2).map(|pair| (pair[0], pair[1])));
+                for node in self.path.drain(..) {
+                    self.path_index[node.pos()] = NOT_IN_PATH;
+                }
+                continue;
+            }
+
+            // choose a random neighbor of the current node. Only the node of a 1x1 maze has no
+            // neighbors, and it is visited before any walk begins
+            let next_node = *self.maze
+                .neighbors_iter(&cur_node)
+                .collect::<ArrayVec<GridNode, 4>>()
+                .choose(&mut self.rng)?;
+
+            // if the random neighbor is already in path, there is a loop, so erase it
+            let node_index = self.path_index[next_node.pos()];
+            if node_index != NOT_IN_PATH {
+                for node in self.path.drain(node_index + 1..) {
+                    self.path_index[node.pos()] = NOT_IN_PATH;
+                }
+            } else {
+                // the random neigbor is not going to make a loop, so push it onto the path
+                self.path_index[next_node.pos()] = self.path.len();
+                self.path.push(next_node);
+            }
+            return Some(Event::Visit(next_node));
         }
     }
+}
+
+impl<R: Rng> Steps for Wilsons<R> {
+    fn maze(&self) -> &GridMaze {
+        &self.maze
+    }
 
-    maze
+    fn into_maze(self) -> GridMaze {
+        self.maze
+    }
 }
@@ -2,3 +2,5 @@
 pub mod generator;
 pub mod solver;
 pub mod maze;
+pub mod formats;
+pub mod analysis;
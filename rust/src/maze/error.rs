@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+/// MazeError is returned when a maze can't be created with the requested dimensions, or when a
+/// maze operation is given nodes it can't be applied to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MazeError {
+    /// a maze must have at least one row and one column
+    ZeroDimension { rows: usize, cols: usize },
+    /// `rows * cols` doesn't fit in a `usize`, or the nodes of the maze can't be allocated
+    TooLarge { rows: usize, cols: usize },
+    /// passages can only be carved between north, east, south or west neighbors, so the nodes at
+    /// these positions can't be linked
+    NotNeighbors { from: usize, to: usize },
+}
+
+impl Display for MazeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MazeError::ZeroDimension { rows, cols } => {
+                write!(f, "a {}x{} maze has no nodes, rows and cols must be at least 1", rows, cols)
+            }
+            MazeError::TooLarge { rows, cols } => write!(f, "a {}x{} maze has too many nodes", rows, cols),
+            MazeError::NotNeighbors { from, to } => {
+                write!(f, "can't link node {} to node {}, they are not neighbors", from, to)
+            }
+        }
+    }
+}
+
+impl Error for MazeError {}
@@ -1,44 +1,108 @@
 use crate::maze::grid_node::{GridNode};
-use std::collections::HashMap;
+use std::collections::BinaryHeap;
+use std::cmp::Reverse;
 use rand::{thread_rng, Rng};
 use std::slice::{ChunksExact, Iter, IterMut};
 use std::ops::Index;
 use std::fmt::{Display, Formatter, Write};
 use rand::seq::SliceRandom;
+use arrayvec::ArrayVec;
 use crate::solver::distances::Distances;
+use crate::maze::error::MazeError;
+
+// the direction bits of a node's links
+const NORTH: u8 = 0b0001;
+const EAST: u8 = 0b0010;
+const SOUTH: u8 = 0b0100;
+const WEST: u8 = 0b1000;
+
+/// returns the direction bit pointing the opposite way of `dir`
+fn opposite(dir: u8) -> u8 {
+    match dir {
+        NORTH => SOUTH,
+        EAST => WEST,
+        SOUTH => NORTH,
+        _ => EAST,
+    }
+}
 
 /// GridMaze represents a two-dimensional maze, with each node having four possible directions that
 /// could be taken to get to another node.
 /// If there is a link between two nodes, that indicates there is a passage "carved" between them.
 ///
 /// The GridNodes are stored in row-order within a one-dimensional Vec
-#[derive(Debug)]
+///
+/// With the `serde` feature enabled, a GridMaze is serialized using the following, versioned,
+/// schema (shown as JSON):
+///
+/// ```json
+/// {
+///   "version": 2,
+///   "rows": 2,
+///   "cols": 2,
+///   "nodes": [{"pos": 0, "weight": 1}, {"pos": 1, "weight": 1}, {"pos": 2, "weight": 1}, {"pos": 3, "weight": 1}],
+///   "links": [[0, 1], [1, 0], [1, 3], [3, 1]],
+///   "start": 0,
+///   "goal": 3
+/// }
+/// ```
+///
+/// - `nodes` holds all `rows * cols` nodes in row order, the `pos` of each node must be its index
+/// - `links` holds every (one-way) link as a `[from, to]` pair of node positions. Links are listed
+///   in order of `from`, and then in order of `to`, so a bi-directional link appears twice. Links
+///   must be between neighboring nodes
+/// - `start` and `goal` are the positions of the nodes where solving the maze begins and ends.
+///   They were added in version 2, version 1 documents are still read and use the north-west and
+///   south-east corners
+///
+/// Deserializing validates the dimensions, node positions, link positions and the start and goal.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "GridMazeRepr", try_from = "GridMazeRepr"))]
 pub struct GridMaze {
     nodes: Vec<GridNode>,
-    // holds links between two nodes in the maze, it maps a `Node.pos` to a Vec of nodes that
-    // have had passages carved between them
-    links: HashMap<usize, Vec<usize>>,
+    // holds the links out of every node, indexed by the node's `pos`. Each node has a bit for
+    // each direction (`NORTH`, `EAST`, `SOUTH` and `WEST`) that is set if a passage has been
+    // carved from it to its neighbor in that direction
+    links: Vec<u8>,
     rows: usize,
     cols: usize,
+    // the positions of the nodes where solving the maze begins and ends
+    start: usize,
+    goal: usize,
 }
 
 impl GridMaze {
     /// constructs a new maze with the specified dimensions, using the `GridNode` type to represent
     /// each node of the maze. Nodes will be stored in row-order. Each node will have a default
     /// weight of 1 and its default `pos` value will be its one-dimensional index within the maze.
-    pub fn new(rows: usize, cols: usize) -> Self {
-        let nodes = (0..(rows * cols))
-            .map(|i| GridNode::new(i, 1))
-            .collect();
-
-        let links: HashMap<usize, Vec<usize>> = HashMap::new();
-
-        Self {
+    /// The start of the maze is its north-west corner and the goal is its south-east corner.
+    ///
+    /// Returns an error if `rows` or `cols` is 0, or if the nodes of a `rows * cols` maze can't be
+    /// allocated
+    pub fn new(rows: usize, cols: usize) -> Result<Self, MazeError> {
+        if rows == 0 || cols == 0 {
+            return Err(MazeError::ZeroDimension { rows, cols });
+        }
+        let too_large = MazeError::TooLarge { rows, cols };
+        let len = rows.checked_mul(cols).ok_or(too_large.clone())?;
+
+        // reserving fails, instead of panicking or aborting, if the nodes don't fit in memory
+        let mut nodes = Vec::new();
+        nodes.try_reserve_exact(len).map_err(|_| too_large.clone())?;
+        nodes.extend((0..len).map(|i| GridNode::new(i, 1)));
+        let mut links = Vec::new();
+        links.try_reserve_exact(len).map_err(|_| too_large)?;
+        links.resize(len, 0);
+
+        Ok(Self {
             nodes,
             links,
             rows,
             cols,
-        }
+            start: 0,
+            goal: len - 1,
+        })
     }
 
     /// returns the dimensions of the maze as a (row, col) tuple
@@ -51,11 +115,34 @@ impl GridMaze {
         self.rows * self.cols
     }
 
-    /// returns true if this GridMaze does not have any nodes in its internal rows Vector
+    /// returns true if this GridMaze does not have any nodes in its internal rows Vector. This is
+    /// never the case for a maze returned by `new`
     pub fn is_empty(&self) -> bool {
         self.nodes.len() == 0
     }
 
+    /// returns the node where solving the maze begins
+    pub fn start(&self) -> GridNode {
+        self.nodes[self.start]
+    }
+
+    /// returns the node where solving the maze ends
+    pub fn goal(&self) -> GridNode {
+        self.nodes[self.goal]
+    }
+
+    /// sets the node where solving the maze begins. Panics if `node` is not in the maze
+    pub fn set_start(&mut self, node: &GridNode) {
+        assert!(node.pos() < self.len(), "start node {} is outside of the maze", node.pos());
+        self.start = node.pos();
+    }
+
+    /// sets the node where solving the maze ends. Panics if `node` is not in the maze
+    pub fn set_goal(&mut self, node: &GridNode) {
+        assert!(node.pos() < self.len(), "goal node {} is outside of the maze", node.pos());
+        self.goal = node.pos();
+    }
+
     /// returns a one-dimensional index based on the given row, col values
     /// `col_dim` is the number of columns in the maze
     pub fn idx_1d(row: usize, col: usize, col_dim: usize) -> usize { row * col_dim + col }
@@ -76,78 +163,135 @@ impl GridMaze {
         }
     }
 
+    /// returns the direction bit of the link from the node at `from` to the node at `to`, or
+    /// `None` if they are not neighbors
+    fn direction(&self, from: usize, to: usize) -> Option<u8> {
+        if from >= self.len() || to >= self.len() {
+            None
+        } else if to + self.cols == from {
+            Some(NORTH)
+        } else if from + self.cols == to {
+            Some(SOUTH)
+        } else if to == from + 1 && !to.is_multiple_of(self.cols) {
+            Some(EAST)
+        } else if from == to + 1 && !from.is_multiple_of(self.cols) {
+            Some(WEST)
+        } else {
+            None
+        }
+    }
+
     /// create a link between two nodes in the maze.
     /// This will essentially "carves" a passageway between them.
     /// `bi_link` creates a bi-directional link. If it is `true`, then in addition to
-    /// creating a link from node1 => node2,  a link is also created from node2 => node1
+    /// creating a link from node1 => node2,  a link is also created from node2 => node1.
+    ///
+    /// Passages can only be carved between neighbors, so if the nodes are not neighbors (or are
+    /// the same node) nothing is linked. Use `try_link` to find out if the link was made
     pub fn link(&mut self, node1: &GridNode, node2: &GridNode, bi_link: bool) {
-        self.links.entry(node1.pos())
-            .or_default()
-            .push(node2.pos());
+        let _ = self.try_link(node1, node2, bi_link);
+    }
+
+    /// the same as `link`, but returns a `MazeError::NotNeighbors` if the nodes are not
+    /// neighbors, in which case the maze is unchanged
+    pub fn try_link(&mut self, node1: &GridNode, node2: &GridNode, bi_link: bool) -> Result<(), MazeError> {
+        let dir = self
+            .direction(node1.pos(), node2.pos())
+            .ok_or(MazeError::NotNeighbors { from: node1.pos(), to: node2.pos() })?;
+        self.links[node1.pos()] |= dir;
         if bi_link {
-            self.links.entry(node2.pos())
-                .or_default()
-                .push(node1.pos());
+            self.links[node2.pos()] |= opposite(dir);
         }
+        Ok(())
     }
 
-    /// unlinks `node1` from `node2`.
+    /// unlinks `node1` from `node2`, and `node2` from `node1`.
     /// If there was no link between then nodes, then this will do nothing
     pub fn unlink(&mut self, node1: &GridNode, node2: &GridNode) {
-        self.links
-            .entry(node1.pos())
-            .and_modify(|ls| {
-                ls.retain(|node_pos| *node_pos != node2.pos())
-            });
+        if let Some(dir) = self.direction(node1.pos(), node2.pos()) {
+            self.links[node1.pos()] &= !dir;
+            self.links[node2.pos()] &= !opposite(dir);
+        }
+    }
 
-        self.links
-            .entry(node2.pos())
-            .and_modify(|ls| {
-                ls.retain(|node_pos| *node_pos != node1.pos())
-            });
-    }
-
-
-    /// returns copies of the GridNode(s) that the given `node` links to.
-    /// In this particular maze, each node can have at most 4 links, or edges, to another Node.
-    /// If the given node doesn't link to anything, an empty Vector is returned
-    pub fn get_links(&self, node: &GridNode) -> Vec<GridNode> {
-        match self.links.get(&node.pos()) {
-            Some(linked_pos) => {
-                linked_pos.iter()
-                    .map(|pos| self.nodes[*pos])
-                    .collect()
-            },
-            None => Vec::new(),
+    /// returns the direction bits of the links out of every node, indexed by the node's `pos`
+    pub(crate) fn link_bits(&self) -> &[u8] {
+        &self.links
+    }
+
+    /// returns the direction bits of the links out of every node, for writing. A bit must only
+    /// be set if the node has a neighbor in that direction
+    pub(crate) fn link_bits_mut(&mut self) -> &mut [u8] {
+        &mut self.links
+    }
+
+    /// returns copies of the GridNode(s) that the given `node` links to, in order of their `pos`
+    /// (north, west, east, south).
+    /// In this particular maze, each node can have at most 4 links, or edges, to another Node, so
+    /// the links are returned in a fixed size `ArrayVec` that does not allocate.
+    /// If the given node doesn't link to anything, an empty ArrayVec is returned
+    pub fn get_links(&self, node: &GridNode) -> ArrayVec<GridNode, 4> {
+        let pos = node.pos();
+        let bits = self.links[pos];
+        let mut linked = ArrayVec::new();
+        // a direction bit is only ever set when there is a neighbor in that direction
+        if bits & NORTH != 0 {
+            linked.push(self.nodes[pos - self.cols]);
+        }
+        if bits & WEST != 0 {
+            linked.push(self.nodes[pos - 1]);
         }
+        if bits & EAST != 0 {
+            linked.push(self.nodes[pos + 1]);
+        }
+        if bits & SOUTH != 0 {
+            linked.push(self.nodes[pos + self.cols]);
+        }
+        linked
     }
 
     /// returns `true` if there is a link between `node1` and `node2`, else `false`.
     /// Note this function only checks one-way links, it will not check for a link between
     /// `node2` and `node1`
     pub fn has_link(&self, node1: &GridNode, node2: &GridNode) -> bool {
-        match self.links.get(&node1.pos()) {
-            Some(node_links) => node_links.contains(&node2.pos()),
-            None => false,
-        }
+        self.direction(node1.pos(), node2.pos())
+            .is_some_and(|dir| self.links[node1.pos()] & dir != 0)
     }
 
     /// returns the neighbors of the given `node`. Neighbors are the nodes adjacent to `node` but NOT
     /// necessarily linked to `node`. To get the linked nodes, use the `get_links()` function
     pub fn neighbors(&self, node: &GridNode) -> Vec<GridNode> {
-        let neighbors = vec![
-            self.north(node),
-            self.east(node),
-            self.south(node),
-            self.west(node),
-        ];
+        self.neighbors_iter(node).collect()
+    }
 
-        neighbors.into_iter().flatten().collect()
+    /// returns an iterator over the neighbors of the given `node`, in north, east, south, west
+    /// order. This is the same as `neighbors()`, without allocating a Vec
+    pub fn neighbors_iter(&self, node: &GridNode) -> impl Iterator<Item = GridNode> {
+        [self.north(node), self.east(node), self.south(node), self.west(node)]
+            .into_iter()
+            .flatten()
+    }
+
+    /// returns an iterator over the nodes that the given `node` links to, in the same order as
+    /// `get_links()`
+    pub fn links_iter(&self, node: &GridNode) -> impl Iterator<Item = GridNode> {
+        self.get_links(node).into_iter()
+    }
+
+    /// returns an iterator over the neighbors of the given `node` that are not linked to any
+    /// node. While a maze is being generated, these are the neighbors that haven't been visited
+    pub fn unlinked_neighbors_iter<'a>(&'a self, node: &GridNode) -> impl Iterator<Item = GridNode> + 'a {
+        self.neighbors_iter(node).filter(move |neighbor| self.links[neighbor.pos()] == 0)
     }
 
     /// returns a copy of a random node in the maze
     pub fn random_node(&self) -> GridNode {
-        let rand_idx = thread_rng().gen_range(0, self.nodes.len());
+        self.random_node_with(&mut thread_rng())
+    }
+
+    /// returns a copy of a random node in the maze, using `rng` as the source of randomness
+    pub fn random_node_with<R: Rng + ?Sized>(&self, rng: &mut R) -> GridNode {
+        let rand_idx = rng.gen_range(0, self.nodes.len());
         self.nodes[rand_idx]
     }
 
@@ -201,7 +345,7 @@ impl GridMaze {
     /// west, `None` is returned
     pub fn west(&self, node: &GridNode) -> Option<GridNode> {
         // if node is not on the western edge of the maze
-        if node.pos() % self.cols != 0 {
+        if !node.pos().is_multiple_of(self.cols) {
             self.nodes.get(node.pos() - 1).copied()
         } else {
             None
@@ -267,8 +411,9 @@ impl GridMaze {
     pub fn dead_ends(&self) -> Vec<GridNode> {
         self.links
             .iter()
-            .filter(|(_pos, links)| links.len() == 1)
-            .map(|(&pos, _links)| self.nodes[pos])
+            .enumerate()
+            .filter(|(_pos, bits)| bits.count_ones() == 1)
+            .map(|(pos, _bits)| self.nodes[pos])
             .collect()
     }
 
@@ -288,17 +433,16 @@ impl GridMaze {
                 continue
             } else {
                 // now get neighbor nodes of `node` that are not linked to it
-                let unlinked_neighbors = self.neighbors(&node)
-                    .into_iter()
+                let unlinked_neighbors = self.neighbors_iter(&node)
                     .filter(|neighbor| !self.has_link(&node, neighbor))
-                    .collect::<Vec<GridNode>>();
+                    .collect::<ArrayVec<GridNode, 4>>();
 
                 // try to select a neighbors that are also dead end nodes
                 let mut best_neighbors = unlinked_neighbors
                     .iter()
                     .filter(|&neighbor| self.get_links(neighbor).len() == 1)
                     .copied()
-                    .collect::<Vec<GridNode>>();
+                    .collect::<ArrayVec<GridNode, 4>>();
 
                 // if no best neighbors found, just use the unlinked neighbors
                 if best_neighbors.is_empty() {
@@ -319,38 +463,44 @@ impl GridMaze {
 impl GridMaze {
 
     /// computes the distances from a `root` node to all other nodes in this `maze`, using each node's
-    /// weight to compute the cost.
+    /// weight to compute the cost. Weights must not be negative for the costs to be the cheapest
+    /// ones, every node is visited at most once so the search still ends if they are. Costs
+    /// saturate at `i32::MAX` rather than overflowing.
     /// returns a `Distances` struct containing the computed costs for each GridCell.
     pub fn distances(&self, root: &GridNode) -> Distances {
 
         // weights holds the Positions and current costs (weights) of the shortest path
-        let mut weights = Distances::new(*root);
+        let mut weights = Distances::with_capacity(*root, self.len());
 
-        // pending holds nodes that need to be visited
-        let mut pending = vec![*root];
+        // pending holds the nodes that need to be visited, lowest cost first. A node is pushed
+        // again whenever a cheaper path to it is found, the older entries are skipped when popped
+        let mut pending = BinaryHeap::new();
+        pending.push(Reverse((0i32, root.pos())));
+        // the nodes that have been visited, their cost is final
+        let mut closed = vec![false; self.len()];
 
-        while !pending.is_empty() {
+        while let Some(Reverse((cost, pos))) = pending.pop() {
+            let cur_node = self[pos];
 
-            // sort pending so that cells with lowest weight are at the **end** of pending
-            pending.sort_unstable_by(|&an, &bn| self[bn.pos()].weight().cmp(&self[an.pos()].weight()) );
-
-            // pop the last position from pending, it will have the lowest weight
-            let cur_node = pending.pop().unwrap();
+            // skip stale entries, the node has already been visited along a cheaper path
+            if closed[pos] {
+                continue;
+            }
+            closed[pos] = true;
 
             // iterate thru the linked neighbors and compute the cost of moving into
             // each of them
-            for neighbor_node in self.get_links(&cur_node) {
+            for neighbor_node in self.links_iter(&cur_node) {
 
                 // the total weight of moving into a neighboring node is the total weight
                 // of the current path so far, plus the weight of the neighbor
-                let total_weight = weights.get(&cur_node).unwrap() +
-                    self[neighbor_node.pos()].weight() as i32;
+                let total_weight = cost.saturating_add(self[neighbor_node.pos()].weight() as i32);
 
-                // if the cost of moving into neighbor has not been recorded in the weights vector
+                // if the cost of moving into neighbor has not been recorded
                 // OR the total cost of moving to neighbor is less than the current weight
-                if weights.get(&neighbor_node).is_none() || total_weight < *weights.get(&neighbor_node).unwrap() {
-                    pending.push(neighbor_node);
+                if !closed[neighbor_node.pos()] && weights.get(&neighbor_node).is_none_or(|&c| total_weight < c) {
                     weights.insert(neighbor_node, total_weight);
+                    pending.push(Reverse((total_weight, neighbor_node.pos())));
                 }
             }
         }
@@ -404,19 +554,127 @@ impl GridMaze {
 }
 
 
+/// the current version of the serialized GridMaze schema
+#[cfg(feature = "serde")]
+const SCHEMA_VERSION: u32 = 2;
+
+/// GridMazeRepr is the serialized form of a GridMaze, see the docs of `GridMaze` for its schema
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GridMazeRepr {
+    version: u32,
+    rows: usize,
+    cols: usize,
+    nodes: Vec<GridNode>,
+    links: Vec<(usize, usize)>,
+    // added in version 2, version 1 mazes start and end at the NW and SE corners
+    #[serde(default)]
+    start: Option<usize>,
+    #[serde(default)]
+    goal: Option<usize>,
+}
+
+#[cfg(feature = "serde")]
+impl From<GridMaze> for GridMazeRepr {
+    fn from(maze: GridMaze) -> Self {
+        let mut links = vec![];
+        for node in maze.iter_nodes() {
+            links.extend(maze.get_links(node).iter().map(|to| (node.pos(), to.pos())));
+        }
+
+        Self {
+            version: SCHEMA_VERSION,
+            rows: maze.rows,
+            cols: maze.cols,
+            nodes: maze.nodes,
+            links,
+            start: Some(maze.start),
+            goal: Some(maze.goal),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GridMazeRepr> for GridMaze {
+    type Error = String;
+
+    fn try_from(repr: GridMazeRepr) -> Result<Self, Self::Error> {
+        if repr.version == 0 || repr.version > SCHEMA_VERSION {
+            return Err(format!("unsupported maze schema version {}", repr.version));
+        }
+        if repr.rows == 0 || repr.cols == 0 {
+            return Err(MazeError::ZeroDimension { rows: repr.rows, cols: repr.cols }.to_string());
+        }
+        if repr.rows.checked_mul(repr.cols) != Some(repr.nodes.len()) {
+            return Err(format!(
+                "a {}x{} maze must have {} nodes, found {}",
+                repr.rows, repr.cols, repr.rows.saturating_mul(repr.cols), repr.nodes.len()
+            ));
+        }
+        if let Some((idx, node)) = repr.nodes.iter().enumerate().find(|(idx, node)| node.pos() != *idx) {
+            return Err(format!("node at index {} has pos {}", idx, node.pos()));
+        }
+
+        let len = repr.nodes.len();
+        let start = repr.start.unwrap_or(0);
+        let goal = repr.goal.unwrap_or(len - 1);
+        if start >= len || goal >= len {
+            return Err(format!("start {} or goal {} is outside of the maze", start, goal));
+        }
+
+        let mut maze = GridMaze {
+            nodes: repr.nodes,
+            links: vec![0; len],
+            rows: repr.rows,
+            cols: repr.cols,
+            start,
+            goal,
+        };
+        for (from, to) in repr.links {
+            if from >= maze.len() || to >= maze.len() {
+                return Err(format!("link [{}, {}] is outside of the maze", from, to));
+            }
+            if maze.direction(from, to).is_none() {
+                return Err(format!("link [{}, {}] is not between neighboring nodes", from, to));
+            }
+            let (node1, node2) = (maze[from], maze[to]);
+            maze.link(&node1, &node2, false);
+        }
+        Ok(maze)
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::GridMaze;
+    use crate::maze::error::MazeError;
 
     #[test]
     fn create_new_maze_with_9_nodes() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         assert_eq!(maze.nodes.len(), 9);
     }
 
+    #[test]
+    fn should_not_create_mazes_without_nodes() {
+        assert_eq!(GridMaze::new(0, 5).unwrap_err(), MazeError::ZeroDimension { rows: 0, cols: 5 });
+        assert_eq!(GridMaze::new(3, 0).unwrap_err(), MazeError::ZeroDimension { rows: 3, cols: 0 });
+        assert_eq!(GridMaze::new(usize::MAX, 2).unwrap_err(), MazeError::TooLarge { rows: usize::MAX, cols: 2 });
+        // rows * cols fits in a usize, but the nodes would need more than isize::MAX bytes
+        let rows = usize::MAX / 2;
+        assert_eq!(GridMaze::new(rows, 1).unwrap_err(), MazeError::TooLarge { rows, cols: 1 });
+        let rows = isize::MAX as usize / std::mem::size_of::<crate::maze::grid_node::GridNode>() + 1;
+        assert_eq!(GridMaze::new(rows, 1).unwrap_err(), MazeError::TooLarge { rows, cols: 1 });
+
+        let maze = GridMaze::new(1, 1).unwrap();
+        assert_eq!(maze.start(), maze.goal());
+        assert_eq!(maze.iter_rows().count(), 1);
+    }
+
     #[test]
     fn should_create_new_maze_with_all_nodes_of_weight_1() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         for node in &maze.nodes {
             assert_eq!(node.weight(), 1);
         }
@@ -424,7 +682,7 @@ mod tests {
 
     #[test]
     fn maze_has_3x4_dimension() {
-        let maze = GridMaze::new(3, 4);
+        let maze = GridMaze::new(3, 4).unwrap();
         let (r, c) = maze.dimensions();
         assert_eq!(r, 3);
         assert_eq!(c, 4);
@@ -432,19 +690,64 @@ mod tests {
 
     #[test]
     fn should_bi_link_two_nodes() {
-        let mut maze = GridMaze::new(3, 3);
+        let mut maze = GridMaze::new(3, 3).unwrap();
         let n1 = maze[0];
         let n2 = maze[1];
         maze.link(&n1, &n2, true);
-        assert!(maze.links.contains_key(&n1.pos()));
-        assert!(maze.links.get(&n1.pos()).unwrap().contains(&n2.pos()));
-        assert!(maze.links.contains_key(&n2.pos()));
-        assert!(maze.links.get(&n2.pos()).unwrap().contains(&n1.pos()));
+        assert_eq!(maze.links[n1.pos()], super::EAST);
+        assert_eq!(maze.links[n2.pos()], super::WEST);
+        assert!(maze.has_link(&n1, &n2));
+        assert!(maze.has_link(&n2, &n1));
+    }
+
+    #[test]
+    fn should_link_one_way_and_unlink_both_ways() {
+        let mut maze = GridMaze::new(3, 3).unwrap();
+        let (n1, n4, n7) = (maze[1], maze[4], maze[7]);
+        maze.link(&n4, &n7, false);
+        // linking twice doesn't add a second link
+        maze.link(&n4, &n1, true);
+        maze.link(&n4, &n1, true);
+        assert_eq!(maze.get_links(&n4).as_slice(), &[n1, n7]);
+        assert!(!maze.has_link(&n7, &n4));
+
+        maze.unlink(&n1, &n4);
+        assert_eq!(maze.get_links(&n4).as_slice(), &[n7]);
+        assert!(maze.get_links(&n1).is_empty());
+    }
+
+    #[test]
+    fn iterators_should_agree_with_their_vec_versions() {
+        let mut maze = GridMaze::new(3, 3).unwrap();
+        let (n1, n3, n4, n5) = (maze[1], maze[3], maze[4], maze[5]);
+        maze.link(&n4, &n5, true);
+        maze.link(&n1, &n4, true);
+
+        for node in maze.iter_nodes() {
+            assert_eq!(maze.neighbors_iter(node).collect::<Vec<_>>(), maze.neighbors(node));
+            assert_eq!(maze.links_iter(node).collect::<Vec<_>>(), maze.get_links(node).to_vec());
+        }
+        // n4's neighbors n1 and n5 are linked, n3 and n7 are not linked to anything
+        assert_eq!(maze.unlinked_neighbors_iter(&n4).collect::<Vec<_>>(), vec![maze[7], n3]);
+    }
+
+    #[test]
+    fn should_not_link_nodes_that_are_not_neighbors() {
+        let mut maze = GridMaze::new(3, 3).unwrap();
+        // the last node of a row is not a neighbor of the first node of the next row
+        let (n2, n3) = (maze[2], maze[3]);
+        assert_eq!(maze.try_link(&n2, &n3, true), Err(MazeError::NotNeighbors { from: 2, to: 3 }));
+        assert_eq!(maze.try_link(&n2, &n2, false), Err(MazeError::NotNeighbors { from: 2, to: 2 }));
+        maze.link(&n2, &n3, true);
+        assert!(maze.get_links(&n2).is_empty());
+        assert!(maze.get_links(&n3).is_empty());
+        let n4 = maze[4];
+        assert_eq!(maze.try_link(&n3, &n4, false), Ok(()));
     }
 
     #[test]
     fn should_get_links() {
-        let mut maze = GridMaze::new(3, 3);
+        let mut maze = GridMaze::new(3, 3).unwrap();
         let n00 = maze[0];
         let n01 = maze[1];
         let n10 = maze[3];
@@ -457,7 +760,7 @@ mod tests {
 
     #[test]
     fn should_index_into_graph() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         // get the node at row 1, column 1, who's one-dimensional index should = 4;
         let node11 = maze[4];
         assert_eq!(node11.pos(), 4);
@@ -465,49 +768,49 @@ mod tests {
 
     #[test]
     fn node_0_should_not_have_north_neighbor() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         let node0 = maze[0];
         assert_eq!(maze.north(&node0), None);
     }
 
     #[test]
     fn node_0_should_not_have_west_neighbor() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         let node0 = maze[0];
         assert_eq!(maze.west(&node0), None);
     }
 
     #[test]
     fn node_6_should_have_north_neighbor() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         let node6 = maze[6];
         assert_eq!(maze.north(&node6), Some(maze[3]));
     }
 
     #[test]
     fn node_2_should_not_have_east_neighbor() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         let node = maze[2];
         assert_eq!(maze.east(&node), None);
     }
 
     #[test]
     fn node_3_should_have_east_neighbor() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         let node = maze[3];
         assert_eq!(maze.east(&node), Some(maze[4]));
     }
 
     #[test]
     fn node_8_should_not_have_east_neighbor() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         let node = maze[8];
         assert_eq!(maze.east(&node), None);
     }
 
     #[test]
     fn node_4_should_have_all_neighbors() {
-        let maze = GridMaze::new(3, 3);
+        let maze = GridMaze::new(3, 3).unwrap();
         let node = maze[4];
         assert_eq!(maze.east(&node), Some(maze[5]));
         assert_eq!(maze.north(&node), Some(maze[1]));
@@ -517,11 +820,78 @@ mod tests {
 
     #[test]
     fn should_display_maze_with_link_from_00_to_01() {
-        let mut maze = GridMaze::new(4, 4);
+        let mut maze = GridMaze::new(4, 4).unwrap();
         let n00 = maze[0];
         let n01 = maze[1];
         maze.link(&n00, &n01, true);
         println!("{}", &maze);
     }
 
+    #[test]
+    fn distances_should_use_cheapest_weighted_path() {
+        // a 2x2 loop, where the path through node 1 is made expensive
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n2, n3) = (maze[0], maze[1], maze[2], maze[3]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n3, true);
+        maze.link(&n0, &n2, true);
+        maze.link(&n2, &n3, true);
+        maze.iter_mut_nodes().nth(1).unwrap().set_weight(5);
+
+        let distances = maze.distances(&n0);
+        assert_eq!(distances[n0], 0);
+        assert_eq!(distances[n1], 5);
+        assert_eq!(distances[n2], 1);
+        assert_eq!(distances[n3], 2);
+
+        let unlinked = GridMaze::new(2, 2).unwrap();
+        let distances = unlinked.distances(&n3);
+        assert_eq!(distances.get(&n3), Some(&0));
+        assert_eq!(distances.get(&n0), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_round_trip_maze_through_json() {
+        let mut maze = GridMaze::new(2, 3).unwrap();
+        let (n0, n1, n4) = (maze[0], maze[1], maze[4]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n4, true);
+        maze.iter_mut_nodes().nth(5).unwrap().set_weight(7);
+
+        let json = serde_json::to_string(&maze).unwrap();
+        assert!(json.starts_with(r#"{"version":2,"rows":2,"cols":3,"nodes":[{"pos":0,"weight":1}"#));
+        assert!(json.ends_with(r#""links":[[0,1],[1,0],[1,4],[4,1]],"start":0,"goal":5}"#));
+
+        let loaded: GridMaze = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.dimensions(), (2, 3));
+        assert_eq!(loaded[5].weight(), 7);
+        assert_eq!(loaded.to_string(), maze.to_string());
+        assert_eq!(serde_json::to_string(&loaded).unwrap(), json);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_reject_invalid_maze_json() {
+        let bad_version = r#"{"version":3,"rows":1,"cols":1,"nodes":[{"pos":0,"weight":1}],"links":[]}"#;
+        assert!(serde_json::from_str::<GridMaze>(bad_version).is_err());
+        let bad_dims = r#"{"version":1,"rows":2,"cols":1,"nodes":[{"pos":0,"weight":1}],"links":[]}"#;
+        assert!(serde_json::from_str::<GridMaze>(bad_dims).is_err());
+        let bad_link = r#"{"version":1,"rows":1,"cols":1,"nodes":[{"pos":0,"weight":1}],"links":[[0,1]]}"#;
+        assert!(serde_json::from_str::<GridMaze>(bad_link).is_err());
+        let self_link = r#"{"version":1,"rows":1,"cols":1,"nodes":[{"pos":0,"weight":1}],"links":[[0,0]]}"#;
+        let err = serde_json::from_str::<GridMaze>(self_link).unwrap_err();
+        assert!(err.to_string().contains("not between neighboring nodes"));
+        let bad_goal = r#"{"version":2,"rows":1,"cols":1,"nodes":[{"pos":0,"weight":1}],"links":[],"start":0,"goal":1}"#;
+        assert!(serde_json::from_str::<GridMaze>(bad_goal).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn should_load_version_1_maze_json() {
+        let v1 = r#"{"version":1,"rows":1,"cols":2,"nodes":[{"pos":0,"weight":1},{"pos":1,"weight":1}],"links":[[0,1],[1,0]]}"#;
+        let maze: GridMaze = serde_json::from_str(v1).unwrap();
+        assert_eq!(maze.start(), maze[0]);
+        assert_eq!(maze.goal(), maze[1]);
+    }
 }
@@ -5,7 +5,10 @@ use std::hash::{Hash, Hasher};
 /// field that is used to uniquely identify a node's position in a maze.
 /// Additionally, GridNodes have a `weight` field that can be used to store cost calculations; useful for maze solvers,
 /// path finding algorithms, etc..
+///
+/// With the `serde` feature enabled, a GridNode is serialized as `{"pos": 4, "weight": 1}`
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GridNode {
     pos: usize,
     weight: isize,
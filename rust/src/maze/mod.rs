@@ -1,5 +1,7 @@
-/// Modules that contain the core structs for representing maze "state"
+//! Modules that contain the core structs for representing maze "state"
 
 pub mod grid_node;
 pub mod four_edge;
-pub mod grid_maze;
\ No newline at end of file
+pub mod grid_maze;
+pub mod error;
+pub mod validation;
@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+
+/// Validation holds the problems found in a maze by `GridMaze::validate`.
+///
+/// Asymmetric (one-way) links are a structural problem, a maze without them is *valid*. A valid
+/// maze that is connected and has no cycles is a spanning tree of the grid, which is what maze
+/// generators call a *perfect* maze: there is exactly one path between any two nodes.
+///
+/// Links between nodes that are not neighbors, or from a node to itself, can't be stored in a
+/// `GridMaze`, so they are never reported.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Validation {
+    /// the nodes that can't be reached from the maze's start by following links
+    pub unreachable: Vec<GridNode>,
+    /// the number of independent cycles formed by the passages, where a passage is a link in
+    /// either direction between two nodes. A braided maze has one cycle for every
+    /// passage it has in addition to those of a spanning tree
+    pub cycles: usize,
+    /// the `(from, to)` links that have no link back from `to` to `from`
+    pub asymmetric_links: Vec<(GridNode, GridNode)>,
+}
+
+impl Validation {
+    /// returns `true` if the maze has no asymmetric links. Unreachable nodes and cycles don't
+    /// make a maze invalid
+    pub fn is_valid(&self) -> bool {
+        self.asymmetric_links.is_empty()
+    }
+
+    /// returns `true` if the maze is valid, every node can be reached from the start and there
+    /// are no cycles
+    pub fn is_perfect(&self) -> bool {
+        self.is_valid() && self.unreachable.is_empty() && self.cycles == 0
+    }
+}
+
+/// prints a one line summary of the problems found
+impl Display for Validation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_perfect() {
+            return write!(f, "perfect maze");
+        }
+        write!(
+            f,
+            "{} unreachable nodes, {} cycles, {} asymmetric links",
+            self.unreachable.len(),
+            self.cycles,
+            self.asymmetric_links.len()
+        )
+    }
+}
+
+/// returns the representative of the set holding `pos`, compressing the path to it
+fn find(parents: &mut [usize], pos: usize) -> usize {
+    let mut root = pos;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    let mut cur = pos;
+    while parents[cur] != root {
+        let next = parents[cur];
+        parents[cur] = root;
+        cur = next;
+    }
+    root
+}
+
+/// Functions to check the structure of a maze
+impl GridMaze {
+
+    /// checks the links of this maze and returns the problems that were found, see `Validation`.
+    ///
+    /// The number of cycles is the cyclomatic number of the passages: the number of passages,
+    /// minus the number of nodes, plus the number of connected groups of nodes.
+    ///
+    /// `Validation` used to have `non_adjacent_links` and `self_links` fields. They were removed,
+    /// as a `GridMaze` can't store those links: `try_link` rejects them with
+    /// `MazeError::NotNeighbors`.
+    pub fn validate(&self) -> Validation {
+        let mut validation = Validation::default();
+        let mut passages: Vec<(usize, usize)> = vec![];
+
+        for node in self.iter_nodes() {
+            for linked in self.get_links(node) {
+                if !self.has_link(&linked, node) {
+                    validation.asymmetric_links.push((*node, linked));
+                    passages.push((node.pos(), linked.pos()));
+                } else if node.pos() < linked.pos() {
+                    // a bi-directional link is one passage
+                    passages.push((node.pos(), linked.pos()));
+                }
+            }
+        }
+
+        // count the connected groups of nodes, ignoring the direction of the passages
+        let mut parents: Vec<usize> = (0..self.len()).collect();
+        let mut groups = self.len();
+        for (pos1, pos2) in passages.iter() {
+            let (root1, root2) = (find(&mut parents, *pos1), find(&mut parents, *pos2));
+            if root1 != root2 {
+                parents[root1] = root2;
+                groups -= 1;
+            }
+        }
+        validation.cycles = passages.len() + groups - self.len();
+
+        // breadth first search from the start, following the links in the direction they were made
+        let mut reached = vec![false; self.len()];
+        let mut pending = VecDeque::from(vec![self.start()]);
+        reached[self.start().pos()] = true;
+        while let Some(node) = pending.pop_front() {
+            for linked in self.get_links(&node) {
+                if !reached[linked.pos()] {
+                    reached[linked.pos()] = true;
+                    pending.push_back(linked);
+                }
+            }
+        }
+        validation.unreachable = self.iter_nodes()
+            .filter(|node| !reached[node.pos()])
+            .copied()
+            .collect();
+
+        validation
+    }
+
+    /// returns `true` if this maze is a perfect maze, i.e. a spanning tree of the grid with only
+    /// bi-directional links. See `validate` for the details of what is checked
+    pub fn is_perfect(&self) -> bool {
+        self.validate().is_perfect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn every_generator_should_produce_perfect_mazes() {
+        for algo in Algorithm::ALL.iter() {
+            for seed in 0..5 {
+                let maze = algo.generate_with_rng(7, 9, StdRng::seed_from_u64(seed)).unwrap();
+                let validation = maze.validate();
+                assert!(validation.is_perfect(), "{} seed {}: {}", algo, seed, validation);
+            }
+        }
+    }
+
+    #[test]
+    fn braided_maze_should_be_valid_with_cycles() {
+        let mut maze = Algorithm::RecursiveBacktracker.generate_with_rng(8, 8, StdRng::seed_from_u64(1)).unwrap();
+        maze.braid(1.0);
+        let validation = maze.validate();
+        assert!(validation.is_valid());
+        assert!(validation.cycles > 0);
+        assert!(validation.unreachable.is_empty());
+        assert!(!maze.is_perfect());
+    }
+
+    #[test]
+    fn should_report_unreachable_nodes_and_cycles() {
+        // a loop around the top two rows of a 3x2 maze, the bottom row is cut off
+        let mut maze = GridMaze::new(3, 2).unwrap();
+        let (n0, n1, n2, n3) = (maze[0], maze[1], maze[2], maze[3]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n3, true);
+        maze.link(&n3, &n2, true);
+        maze.link(&n2, &n0, true);
+        let validation = maze.validate();
+        assert!(validation.is_valid());
+        assert_eq!(validation.cycles, 1);
+        assert_eq!(validation.unreachable, vec![maze[4], maze[5]]);
+        assert_eq!(validation.to_string(), "2 unreachable nodes, 1 cycles, 0 asymmetric links");
+    }
+
+    #[test]
+    fn should_report_one_way_links() {
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n2, n3) = (maze[0], maze[1], maze[2], maze[3]);
+        maze.link(&n0, &n1, false);
+        maze.link(&n1, &n3, true);
+        maze.link(&n2, &n0, false);
+        let validation = maze.validate();
+        assert!(!validation.is_valid());
+        assert_eq!(validation.asymmetric_links, vec![(n0, n1), (n2, n0)]);
+        assert_eq!(validation.cycles, 0);
+        // node 2 links to the start, but can't be reached from it
+        assert_eq!(validation.unreachable, vec![n2]);
+    }
+}
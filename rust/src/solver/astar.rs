@@ -0,0 +1,211 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::path::Path;
+
+/// Heuristic estimates the cost of the path from a node to the goal.
+///
+/// The estimates are in cells, multiplied by the smallest node weight in the maze, so they never
+/// over-estimate the cost of a path and A* always finds a shortest path.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Heuristic {
+    /// the number of rows plus the number of columns between a node and the goal
+    Manhattan,
+    /// the straight line distance between a node and the goal
+    Euclidean,
+    /// always zero, which makes A* behave like Dijkstra's algorithm
+    Zero,
+}
+
+impl Heuristic {
+
+    /// returns the estimated number of cells between `node` and `goal`
+    fn estimate(&self, maze: &GridMaze, node: usize, goal: usize) -> f64 {
+        let (_rows, cols) = maze.dimensions();
+        let d_row = (node / cols).abs_diff(goal / cols) as f64;
+        let d_col = (node % cols).abs_diff(goal % cols) as f64;
+        match self {
+            Heuristic::Manhattan => d_row + d_col,
+            Heuristic::Euclidean => (d_row * d_row + d_col * d_col).sqrt(),
+            Heuristic::Zero => 0.0,
+        }
+    }
+}
+
+/// AStarPath is the result of a successful A* search
+#[derive(Debug, Clone)]
+pub struct AStarPath {
+    /// the shortest path from the start to the goal
+    pub path: Path,
+    /// the number of nodes that were expanded (removed from the open set) during the search
+    pub expanded: usize,
+}
+
+/// an entry of the open set, ordered so that the `BinaryHeap` pops the lowest estimated cost
+/// first, breaking ties in favor of the node farthest along its path
+struct Entry {
+    estimate: f64,
+    cost: i32,
+    pos: usize,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+            .then_with(|| self.cost.cmp(&other.cost))
+    }
+}
+
+/// finds the shortest path in the `maze` from `start` to `goal` using the A* algorithm, guided
+/// by the given `heuristic`. Moving into a node costs that node's weight.
+///
+/// Unlike `dijkstras::shortest_path`, the search is guided towards the goal by the heuristic, so
+/// fewer nodes are usually explored.
+/// Returns `None` if the goal can't be reached from the start.
+pub fn find_path(maze: &GridMaze, start: GridNode, goal: GridNode, heuristic: Heuristic) -> Option<AStarPath> {
+    // scaling by the smallest weight keeps the heuristic from over-estimating
+    let min_weight = maze.iter_nodes().map(|n| n.weight()).min().unwrap_or(0).max(0) as f64;
+
+    // the cheapest known cost of reaching every node, and the node it was reached from
+    let mut costs: Vec<Option<i32>> = vec![None; maze.len()];
+    let mut came_from: Vec<Option<usize>> = vec![None; maze.len()];
+    let mut closed = vec![false; maze.len()];
+    let mut expanded = 0;
+
+    let mut open = BinaryHeap::new();
+    costs[start.pos()] = Some(0);
+    open.push(Entry {
+        estimate: heuristic.estimate(maze, start.pos(), goal.pos()) * min_weight,
+        cost: 0,
+        pos: start.pos(),
+    });
+
+    while let Some(Entry { cost, pos, .. }) = open.pop() {
+        // skip entries that were superseded by a cheaper path
+        if closed[pos] {
+            continue;
+        }
+        closed[pos] = true;
+        expanded += 1;
+
+        if pos == goal.pos() {
+            let mut path = vec![maze[pos]];
+            let mut cur = pos;
+            while let Some(prev) = came_from[cur] {
+                path.push(maze[prev]);
+                cur = prev;
+            }
+            path.reverse();
+            return Some(AStarPath { path: Path::new(path), expanded });
+        }
+
+        for neighbor in maze.get_links(&maze[pos]) {
+            let n_pos = neighbor.pos();
+            let neighbor_cost = cost.saturating_add(maze[n_pos].weight() as i32);
+            if !closed[n_pos] && costs[n_pos].is_none_or(|c| neighbor_cost < c) {
+                costs[n_pos] = Some(neighbor_cost);
+                came_from[n_pos] = Some(pos);
+                open.push(Entry {
+                    estimate: neighbor_cost as f64 + heuristic.estimate(maze, n_pos, goal.pos()) * min_weight,
+                    cost: neighbor_cost,
+                    pos: n_pos,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{find_path, Heuristic};
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+    use crate::generator::recursive_backtracker;
+
+    const HEURISTICS: [Heuristic; 3] = [Heuristic::Manhattan, Heuristic::Euclidean, Heuristic::Zero];
+
+    #[test]
+    fn should_match_the_cost_of_dijkstras() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let mut maze = algo.generate_with_rng(12, 12, StdRng::seed_from_u64(i as u64)).unwrap();
+            maze.braid(0.5);
+            let (start, goal) = (maze[0], maze[maze.len() - 1]);
+            let distances = maze.distances(&start);
+
+            for heuristic in HEURISTICS.iter() {
+                let found = find_path(&maze, start, goal, *heuristic).unwrap();
+                assert_eq!(found.path.cost(), distances[goal], "{} with {:?}", algo, heuristic);
+                assert_eq!(found.path.start(), Some(start));
+                assert_eq!(found.path.goal(), Some(goal));
+                for step in found.path.nodes().windows(2) {
+                    assert!(maze.has_link(&step[0], &step[1]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn manhattan_should_expand_fewer_nodes_than_zero() {
+        // an open grid, where the heuristic can lead the search straight to the goal
+        let mut maze = GridMaze::new(20, 20).unwrap();
+        let nodes: Vec<_> = maze.iter_nodes().copied().collect();
+        for node in nodes.iter() {
+            for neighbor in [maze.east(node), maze.south(node)].iter().flatten() {
+                maze.link(node, neighbor, true);
+            }
+        }
+        let (start, goal) = (maze[0], maze[maze.len() - 1]);
+        let manhattan = find_path(&maze, start, goal, Heuristic::Manhattan).unwrap();
+        let zero = find_path(&maze, start, goal, Heuristic::Zero).unwrap();
+        assert_eq!(manhattan.path.cost(), 38);
+        assert_eq!(zero.path.cost(), 38);
+        assert!(manhattan.expanded < zero.expanded);
+    }
+
+    #[test]
+    fn should_avoid_heavy_nodes() {
+        // a 2x2 loop, where the path through node 1 is made expensive
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n2, n3) = (maze[0], maze[1], maze[2], maze[3]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n3, true);
+        maze.link(&n0, &n2, true);
+        maze.link(&n2, &n3, true);
+        maze.iter_mut_nodes().nth(1).unwrap().set_weight(10);
+
+        let found = find_path(&maze, n0, n3, Heuristic::Manhattan).unwrap();
+        assert_eq!(found.path.nodes(), &[n0, n2, n3]);
+        assert_eq!(found.path.cost(), 2);
+    }
+
+    #[test]
+    fn should_return_none_when_goal_is_unreachable() {
+        let maze = GridMaze::new(3, 3).unwrap();
+        assert!(find_path(&maze, maze[0], maze[8], Heuristic::Manhattan).is_none());
+
+        let maze = recursive_backtracker::generate(3, 3).unwrap();
+        let found = find_path(&maze, maze[4], maze[4], Heuristic::Zero).unwrap();
+        assert_eq!(found.path.nodes(), &[maze[4]]);
+        assert_eq!(found.path.cost(), 0);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::walk::Walk;
+
+/// returns the passages out of `node` into nodes that haven't been `filled`
+fn open_passages(maze: &GridMaze, node: &GridNode, filled: &[bool]) -> Vec<GridNode> {
+    maze.neighbors(node)
+        .into_iter()
+        .filter(|neighbor| !filled[neighbor.pos()] && maze.has_link(node, neighbor))
+        .collect()
+}
+
+/// solves the `maze` using dead-end filling.
+///
+/// Every dead-end, other than the `start` and `goal`, is "filled in", and filling continues
+/// back along its passage until a junction is reached. What is left unfilled is the solution.
+/// It is then walked from the start, depth first, until the goal is reached. In a perfect maze
+/// the unfilled nodes are exactly the path from the start to the goal, but in a maze with loops
+/// the walk may explore (and back out of) some of the loops.
+///
+/// `steps` counts each node filled plus each move of the final walk.
+pub fn solve(maze: &GridMaze, start: GridNode, goal: GridNode) -> Walk {
+    let mut filled = vec![false; maze.len()];
+    let mut filled_count = 0;
+    let is_end = |node: &GridNode| *node == start || *node == goal;
+
+    let mut dead_ends: Vec<GridNode> = maze.iter_nodes()
+        .filter(|node| !is_end(node) && open_passages(maze, node, &filled).len() <= 1)
+        .copied()
+        .collect();
+    while let Some(node) = dead_ends.pop() {
+        if filled[node.pos()] {
+            continue;
+        }
+        filled[node.pos()] = true;
+        filled_count += 1;
+
+        // filling a dead-end can turn the node before it into a dead-end
+        for neighbor in open_passages(maze, &node, &filled) {
+            if !is_end(&neighbor) && open_passages(maze, &neighbor, &filled).len() <= 1 {
+                dead_ends.push(neighbor);
+            }
+        }
+    }
+
+    // walk the unfilled nodes, depth first, from the start to the goal
+    let mut nodes = vec![start];
+    let mut visited = vec![false; maze.len()];
+    visited[start.pos()] = true;
+    let mut stack = vec![start];
+    let mut reached_goal = start == goal;
+    while let Some(&cur_node) = stack.last() {
+        if cur_node == goal {
+            reached_goal = true;
+            break;
+        }
+        let next = open_passages(maze, &cur_node, &filled)
+            .into_iter()
+            .find(|neighbor| !visited[neighbor.pos()]);
+        match next {
+            Some(next_node) => {
+                visited[next_node.pos()] = true;
+                stack.push(next_node);
+                nodes.push(next_node);
+            }
+            None => {
+                // back out of the passage
+                stack.pop();
+                if let Some(&prev) = stack.last() {
+                    nodes.push(prev);
+                }
+            }
+        }
+    }
+
+    let mut walk = Walk::new(nodes, reached_goal);
+    walk.steps += filled_count;
+    walk
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::solve;
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+    use crate::solver::dijkstras::shortest_path;
+
+    #[test]
+    fn should_leave_only_the_solution_of_perfect_mazes() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let maze = algo.generate_with_rng(10, 10, StdRng::seed_from_u64(i as u64)).unwrap();
+            let (start, goal) = (maze[0], maze[maze.len() - 1]);
+            let walk = solve(&maze, start, goal);
+            let path = shortest_path(&maze, start, goal).unwrap();
+
+            assert!(walk.reached_goal, "{}", algo);
+            assert_eq!(walk.nodes.as_slice(), path.nodes(), "{}", algo);
+            // every node not on the path was filled
+            assert_eq!(walk.steps, (maze.len() - path.len()) + (path.len() - 1));
+        }
+    }
+
+    #[test]
+    fn should_find_goal_in_braided_maze() {
+        let mut maze = Algorithm::RecursiveBacktracker.generate_with_rng(10, 10, StdRng::seed_from_u64(3)).unwrap();
+        maze.braid(1.0);
+        let walk = solve(&maze, maze[0], maze[99]);
+        assert!(walk.reached_goal);
+        assert_eq!(walk.nodes.last(), Some(&maze[99]));
+    }
+
+    #[test]
+    fn should_not_reach_unreachable_goal() {
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1) = (maze[0], maze[1]);
+        maze.link(&n0, &n1, true);
+        let walk = solve(&maze, n0, maze[3]);
+        assert!(!walk.reached_goal);
+        // node 1 was filled, so the walk never leaves the start
+        assert_eq!(walk.nodes, vec![n0]);
+    }
+}
@@ -1,40 +1,154 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
 use crate::solver::distances::{Distances};
+use crate::solver::path::Path;
 use crate::maze::grid_maze::GridMaze;
 use crate::maze::grid_node::GridNode;
 
 
 /// finds the shortest path in the `maze`, beginning at `start` and finishing at `goal`
 /// returns a `Distances` struct that only contains the positions of cells on the shortest
-/// path
-pub fn find_shortest_path(maze: &GridMaze, start: GridNode, goal: GridNode) -> Distances {
-    // compute distances for all cells in the maze beginning at start Pos
-    let maze_dist = maze.distances(&start);
-
-    // start from the goal and work backwards towards start
-    let mut cur_node = goal;
-
-    // curr_path will only contain positions that are on the shortest path from goal to start
-    let mut cur_path = Distances::new(start);
-    // insert the current cell into curr_path
-    cur_path.insert(cur_node, maze_dist[cur_node]);
-
-    loop {
-        if cur_node == start {
-            break;
+/// path, or `None` if `goal` can't be reached from `start`
+pub fn find_shortest_path(maze: &GridMaze, start: GridNode, goal: GridNode) -> Option<Distances> {
+    shortest_path(maze, start, goal).and_then(|path| path.to_distances())
+}
+
+/// finds the shortest path in the `maze` from `start` to `goal`, using each node's weight as the
+/// cost of moving into it. Returns `None` if `goal` can't be reached from `start`.
+///
+/// Links are only followed in the direction they were made, so one-way links are handled
+/// correctly. The path is rebuilt from the predecessor of each node, which is recorded as the
+/// search runs, so the search stops as soon as the goal is reached.
+///
+/// Weights must not be negative for the path to be the cheapest one. Every node is visited at
+/// most once, so the search still ends if they are, but the path it returns may not be the
+/// cheapest. Costs saturate at `i32::MAX` rather than overflowing.
+pub fn shortest_path(maze: &GridMaze, start: GridNode, goal: GridNode) -> Option<Path> {
+    // the cheapest known cost of reaching every node, and the node it was reached from
+    let mut costs: Vec<Option<i32>> = vec![None; maze.len()];
+    let mut came_from: Vec<Option<usize>> = vec![None; maze.len()];
+    // the nodes that have been visited, their cost and predecessor are final
+    let mut closed = vec![false; maze.len()];
+
+    let mut pending = BinaryHeap::new();
+    costs[start.pos()] = Some(0);
+    pending.push(Reverse((0i32, start.pos())));
+
+    while let Some(Reverse((cost, pos))) = pending.pop() {
+        // skip stale entries, the node has already been visited along a cheaper path
+        if closed[pos] {
+            continue;
+        }
+        closed[pos] = true;
+
+        if pos == goal.pos() {
+            // walk the predecessors back to the start. A node's predecessor was visited before
+            // it, and the start (the first node visited) has no predecessor, so this always
+            // ends at the start
+            let mut nodes = vec![maze[pos]];
+            let mut cur = pos;
+            while let Some(prev) = came_from[cur] {
+                nodes.push(maze[prev]);
+                cur = prev;
+            }
+            nodes.reverse();
+            return Some(Path::new(nodes));
+        }
+
+        for neighbor in maze.get_links(&maze[pos]) {
+            let n_pos = neighbor.pos();
+            let neighbor_cost = cost.saturating_add(maze[n_pos].weight() as i32);
+            if !closed[n_pos] && costs[n_pos].is_none_or(|c| neighbor_cost < c) {
+                costs[n_pos] = Some(neighbor_cost);
+                came_from[n_pos] = Some(pos);
+                pending.push(Reverse((neighbor_cost, n_pos)));
+            }
         }
+    }
+
+    None
+}
+
 
-        // get the positions of all neighbors to the current cell's position
-        for neighbor_node in maze.get_links(&cur_node) {
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{shortest_path, find_shortest_path};
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
 
-            // if the neighbor's distance is less than the current cell's distance, insert
-            // the neighbor cell into curr_path, and make that neighbor the current cell
-            if maze_dist[neighbor_node] < maze_dist[cur_node] {
-                cur_path.insert(neighbor_node, maze_dist[neighbor_node]);
-                cur_node = neighbor_node;
-                break;
+    #[test]
+    fn should_find_path_in_every_generated_maze() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let maze = algo.generate_with_rng(10, 10, StdRng::seed_from_u64(i as u64)).unwrap();
+            let (start, goal) = (maze[0], maze[maze.len() - 1]);
+            let path = shortest_path(&maze, start, goal).unwrap();
+            assert_eq!(path.cost(), maze.distances(&start)[goal], "{}", algo);
+            assert_eq!(path.start(), Some(start));
+            assert_eq!(path.goal(), Some(goal));
+            for step in path.nodes().windows(2) {
+                assert!(maze.has_link(&step[0], &step[1]));
             }
         }
     }
 
-    cur_path
+    #[test]
+    fn should_return_none_when_goal_is_unreachable() {
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n3) = (maze[0], maze[1], maze[3]);
+        maze.link(&n0, &n1, true);
+        assert!(shortest_path(&maze, n0, n3).is_none());
+        assert!(find_shortest_path(&maze, n0, n3).is_none());
+    }
+
+    #[test]
+    fn should_end_with_negative_and_huge_weights() {
+        // a loop of negative weights, that keeps getting cheaper if it is walked again
+        let mut maze = GridMaze::new(2, 2).unwrap();
+        let (n0, n1, n2, n3) = (maze[0], maze[1], maze[2], maze[3]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n3, true);
+        maze.link(&n3, &n2, true);
+        maze.link(&n2, &n0, true);
+        for node in maze.iter_mut_nodes() {
+            node.set_weight(-5);
+        }
+        let path = shortest_path(&maze, n0, n3).unwrap();
+        assert_eq!(path.start(), Some(n0));
+        assert_eq!(path.goal(), Some(n3));
+        assert_eq!(path.len(), 3);
+        assert_eq!(maze.distances(&n0)[n0], 0);
+
+        // costs saturate instead of overflowing
+        for node in maze.iter_mut_nodes() {
+            node.set_weight(i32::MAX as isize);
+        }
+        assert_eq!(shortest_path(&maze, n0, n3).unwrap().cost(), i32::MAX);
+        assert_eq!(maze.distances(&n0)[n3], i32::MAX);
+    }
+
+    #[test]
+    fn should_only_follow_one_way_links_forwards() {
+        // 0 -> 1 -> 2 is one-way, the only way back from 2 to 0 is through the bottom row
+        let mut maze = GridMaze::new(2, 3).unwrap();
+        let (n0, n1, n2, n3, n4, n5) = (maze[0], maze[1], maze[2], maze[3], maze[4], maze[5]);
+        maze.link(&n0, &n1, false);
+        maze.link(&n1, &n2, false);
+        maze.link(&n2, &n5, true);
+        maze.link(&n5, &n4, true);
+        maze.link(&n4, &n3, true);
+        maze.link(&n3, &n0, true);
+
+        let forwards = shortest_path(&maze, n0, n2).unwrap();
+        assert_eq!(forwards.nodes(), &[n0, n1, n2]);
+        let backwards = shortest_path(&maze, n2, n0).unwrap();
+        assert_eq!(backwards.nodes(), &[n2, n5, n4, n3, n0]);
+        assert!(shortest_path(&maze, n1, n3).is_some());
+        assert!(shortest_path(&maze, n3, n1).is_some());
+
+        let distances = find_shortest_path(&maze, n2, n0).unwrap();
+        assert_eq!(distances.get(&n0), Some(&4));
+        assert_eq!(distances.get(&n1), None);
+    }
 }
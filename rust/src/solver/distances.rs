@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::ops::Index;
 use std::fmt::Write;
 use crate::maze::grid_node::GridNode;
@@ -6,11 +5,30 @@ use crate::maze::grid_maze::GridMaze;
 
 /// Distances is a helper struct that holds how far every node in a Maze is from a `root` cell.
 /// This distance information can be used by shortest-path algorithms (like Dijkstra's)
+///
+/// With the `serde` feature enabled, Distances are serialized using the following schema
+/// (shown as JSON):
+///
+/// ```json
+/// {"root": {"pos": 0, "weight": 1}, "len": 6, "distances": [[0, 0], [1, 1], [4, 2]]}
+/// ```
+///
+/// `len` is one more than the largest `pos` that may have a distance, which is the number of
+/// nodes in the maze for distances returned by `GridMaze::distances`. `distances` holds a
+/// `[pos, distance]` pair for every node that has a distance, ordered by `pos`. Node weights,
+/// other than the root's, are not stored as they belong to the maze.
+///
+/// Deserializing rejects a root or a `pos` that is not below `len`, and a `len` that is too large
+/// to allocate.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "DistancesRepr", try_from = "DistancesRepr"))]
 pub struct Distances {
     // root is the starting node im a maze
     root: GridNode,
-    // stores the 'distance' from a GridNode, to the `root` cell.
-    nodes: HashMap<GridNode, i32>,
+    // stores the 'distance' from a GridNode, to the `root` cell, indexed by the node's `pos`.
+    // `None` means the node has no distance (i.e. it was not reached from the root)
+    nodes: Vec<Option<i32>>,
 }
 
 
@@ -19,24 +37,51 @@ impl Distances {
     /// returns a new Distance struct with the specified `root` GridNode as the root of
     /// the returned distance struct.
     pub fn new(root: GridNode) -> Self {
-        let mut nodes = HashMap::new();
-        // the root is inserted into the hashmap with a distance of 0 from itself
-        nodes.insert(root, 0);
+        Self::with_capacity(root, root.pos() + 1)
+    }
+
+    /// returns a new Distance struct with the specified `root`, that has room for the distances
+    /// of `len` nodes without growing. Use it when the number of nodes in the maze is known.
+    pub fn with_capacity(root: GridNode, len: usize) -> Self {
+        let mut nodes = vec![None; len.max(root.pos() + 1)];
+        // the root has a distance of 0 from itself
+        nodes[root.pos()] = Some(0);
 
         Self { root, nodes }
     }
 
 
+    /// returns the root node that all distances are measured from
+    pub fn root(&self) -> GridNode {
+        self.root
+    }
+
+
     /// returns the distance information for the given `node`. Returns `None` if
     /// the cell is not contained within Distances
     pub fn get(&self, node: &GridNode) -> Option<&i32> {
-        self.nodes.get(node)
+        self.nodes.get(node.pos()).and_then(|dist| dist.as_ref())
     }
 
 
     /// insert the given `node` with `distance` into this struct
     pub fn insert(&mut self, node: GridNode, distance: i32) {
-        self.nodes.insert(node, distance);
+        if node.pos() >= self.nodes.len() {
+            self.nodes.resize(node.pos() + 1, None);
+        }
+        self.nodes[node.pos()] = Some(distance);
+    }
+
+
+    /// returns the `pos` of the node that is farthest from the root, along with its distance. If
+    /// several nodes are equally far, the one with the lowest `pos` is returned.
+    /// Distances only store the positions of nodes, use `maze[pos]` to get the node itself.
+    pub fn max(&self) -> (usize, i32) {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, distance)| distance.map(|d| (pos, d)))
+            .fold((self.root.pos(), 0), |max, cur| if cur.1 > max.1 { cur } else { max })
     }
 }
 
@@ -46,7 +91,55 @@ impl Index<GridNode> for Distances {
     type Output = i32;
 
     fn index(&self, node: GridNode) -> &Self::Output {
-        &self.nodes[&node]
+        self.get(&node).expect("node should have a distance")
+    }
+}
+
+
+/// DistancesRepr is the serialized form of Distances, see the docs of `Distances` for its schema
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DistancesRepr {
+    root: GridNode,
+    len: usize,
+    distances: Vec<(usize, i32)>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Distances> for DistancesRepr {
+    fn from(dist: Distances) -> Self {
+        let distances: Vec<(usize, i32)> = dist.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, distance)| distance.map(|d| (pos, d)))
+            .collect();
+        Self { root: dist.root, len: dist.nodes.len(), distances }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<DistancesRepr> for Distances {
+    type Error = String;
+
+    fn try_from(repr: DistancesRepr) -> Result<Self, Self::Error> {
+        let out_of_range = std::iter::once(repr.root.pos())
+            .chain(repr.distances.iter().map(|(pos, _distance)| *pos))
+            .find(|pos| *pos >= repr.len);
+        if let Some(pos) = out_of_range {
+            return Err(format!("node {} is outside of the {} nodes of the distances", pos, repr.len));
+        }
+
+        // `len` comes from the document, so a failed allocation is an error rather than an abort
+        let mut nodes = Vec::new();
+        nodes
+            .try_reserve_exact(repr.len)
+            .map_err(|_| format!("can't allocate the distances of {} nodes", repr.len))?;
+        nodes.resize(repr.len, None);
+        nodes[repr.root.pos()] = Some(0);
+        for (pos, distance) in repr.distances {
+            nodes[pos] = Some(distance);
+        }
+        Ok(Self { root: repr.root, nodes })
     }
 }
 
@@ -95,3 +188,39 @@ pub fn overlay_distances(maze: &GridMaze, distances: &Distances) -> String {
     }
     buf
 }
+
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::Distances;
+    use crate::maze::grid_node::GridNode;
+
+    #[test]
+    fn should_round_trip_distances_through_json() {
+        let mut dist = Distances::new(GridNode::new(3, 1));
+        dist.insert(GridNode::new(4, 1), 1);
+        dist.insert(GridNode::new(1, 1), 2);
+
+        let json = serde_json::to_string(&dist).unwrap();
+        assert_eq!(json, r#"{"root":{"pos":3,"weight":1},"len":5,"distances":[[1,2],[3,0],[4,1]]}"#);
+
+        let loaded: Distances = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.root(), GridNode::new(3, 1));
+        assert_eq!(loaded.get(&GridNode::new(1, 1)), Some(&2));
+        assert_eq!(loaded.get(&GridNode::new(0, 1)), None);
+    }
+
+    #[test]
+    fn should_reject_hostile_distances_json() {
+        let huge_pos = r#"{"root":{"pos":0,"weight":1},"len":2,"distances":[[1000000000000000000,1]]}"#;
+        let err = serde_json::from_str::<Distances>(huge_pos).err().unwrap();
+        assert!(err.to_string().contains("outside of the 2 nodes"), "{}", err);
+
+        let huge_root = r#"{"root":{"pos":1000000000000000000,"weight":1},"len":2,"distances":[]}"#;
+        assert!(serde_json::from_str::<Distances>(huge_root).is_err());
+
+        let huge_len = r#"{"root":{"pos":0,"weight":1},"len":1000000000000000000,"distances":[]}"#;
+        let err = serde_json::from_str::<Distances>(huge_len).err().unwrap();
+        assert!(err.to_string().contains("can't allocate"), "{}", err);
+    }
+}
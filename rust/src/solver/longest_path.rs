@@ -0,0 +1,127 @@
+use crate::maze::grid_maze::GridMaze;
+use crate::solver::dijkstras::shortest_path;
+use crate::solver::path::Path;
+
+/// returns the longest path in the `maze`, i.e. the path between the two nodes that are farthest
+/// apart. Its length is the "diameter" of the maze, and its ends make the hardest start and goal.
+///
+/// The distances from any node are computed, and the farthest node from it is found. Then the
+/// distances from that node are computed, and the farthest node from it is the other end of the
+/// path. This finds the longest path of a perfect maze whose nodes all have the same weight, and a
+/// good approximation of it in a maze with loops or with weighted nodes. The cost of a weighted
+/// path doesn't include its first node, so it depends on the direction it's walked in: the path
+/// between the two ends is found in both directions, and the costlier one is returned.
+///
+/// The search begins at node 0, the north-west corner, so only the nodes that can be reached from
+/// node 0 are considered.
+pub fn longest_path(maze: &GridMaze) -> Path {
+    let (farthest, _distance) = maze.distances(&maze[0]).max();
+    let from = maze[farthest];
+    let (farthest, _distance) = maze.distances(&from).max();
+    let to = maze[farthest];
+
+    let forwards = shortest_path(maze, from, to).unwrap_or_else(|| Path::new(vec![from]));
+    match shortest_path(maze, to, from) {
+        Some(backwards) if backwards.cost() > forwards.cost() => backwards,
+        _ => forwards,
+    }
+}
+
+/// sets the start and goal of the `maze` to the ends of its longest path, which makes them the
+/// hardest pair of nodes to solve between, and returns the path
+pub fn set_diameter_endpoints(maze: &mut GridMaze) -> Path {
+    let path = longest_path(maze);
+    if let (Some(start), Some(goal)) = (path.start(), path.goal()) {
+        maze.set_start(&start);
+        maze.set_goal(&goal);
+    }
+    path
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{longest_path, set_diameter_endpoints};
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    /// returns the longest shortest path, by computing the distances from every node
+    fn brute_force_diameter(maze: &GridMaze) -> i32 {
+        maze.iter_nodes().map(|node| maze.distances(node).max().1).max().unwrap()
+    }
+
+    #[test]
+    fn should_find_diameter_of_perfect_mazes() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let mut maze = algo.generate_with_rng(9, 11, StdRng::seed_from_u64(i as u64)).unwrap();
+            // Prim's assigns random weights, and the trick is only exact when the weights are
+            // equal. On the weighted maze it finds a real path that is no longer than the diameter
+            let path = longest_path(&maze);
+            assert!(path.cost() <= brute_force_diameter(&maze), "{}", algo);
+            for node in maze.iter_mut_nodes() {
+                node.set_weight(1);
+            }
+
+            let path = longest_path(&maze);
+            assert_eq!(path.cost(), brute_force_diameter(&maze), "{}", algo);
+        }
+    }
+
+    #[test]
+    fn should_find_exact_diameter_of_small_weighted_maze() {
+        // 0 - 1 - 2
+        // |   |   |
+        // 3   4   5
+        let mut maze = GridMaze::new(2, 3).unwrap();
+        let n = (0..6).map(|pos| maze[pos]).collect::<Vec<_>>();
+        for (from, to) in [(0, 1), (1, 2), (0, 3), (1, 4), (2, 5)] {
+            maze.link(&n[from], &n[to], true);
+        }
+        for (node, weight) in maze.iter_mut_nodes().zip([2, 3, 1, 4, 1, 5]) {
+            node.set_weight(weight);
+        }
+
+        // the costliest path is from 3 to 5: 2 + 3 + 1 + 5
+        assert_eq!(brute_force_diameter(&maze), 11);
+        let path = longest_path(&maze);
+        assert_eq!(path.cost(), 11);
+        assert_eq!(path.start(), Some(maze[3]));
+        assert_eq!(path.goal(), Some(maze[5]));
+    }
+
+    #[test]
+    fn max_should_return_position_of_farthest_node() {
+        let mut maze = GridMaze::new(1, 3).unwrap();
+        let (n0, n1, n2) = (maze[0], maze[1], maze[2]);
+        maze.link(&n0, &n1, true);
+        maze.link(&n1, &n2, true);
+        for node in maze.iter_mut_nodes() {
+            node.set_weight(5);
+        }
+        let (farthest, distance) = maze.distances(&maze[0]).max();
+        assert_eq!((farthest, distance), (2, 10));
+        assert_eq!(maze[farthest].weight(), 5);
+    }
+
+    #[test]
+    fn should_set_start_and_goal_to_ends_of_longest_path() {
+        let mut maze = Algorithm::Wilsons.generate_with_rng(8, 8, StdRng::seed_from_u64(2)).unwrap();
+        assert_eq!(maze.start(), maze[0]);
+        assert_eq!(maze.goal(), maze[63]);
+
+        let path = set_diameter_endpoints(&mut maze);
+        assert_eq!(Some(maze.start()), path.start());
+        assert_eq!(Some(maze.goal()), path.goal());
+        assert_eq!(maze.distances(&maze.start())[maze.goal()], path.cost());
+    }
+
+    #[test]
+    fn single_node_maze_should_have_empty_diameter() {
+        let maze = GridMaze::new(1, 1).unwrap();
+        let path = longest_path(&maze);
+        assert_eq!(path.nodes(), &[maze[0]]);
+        assert_eq!(path.cost(), 0);
+    }
+}
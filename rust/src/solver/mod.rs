@@ -1,7 +1,35 @@
-/// modules for solving (finding a path through) a maze
+//! modules for solving (finding a path through) a maze
 
 /// holds distances between cells of a maze
 pub mod distances;
 
 /// find the shortest path through a maze using Dijkstra's algorithm
 pub mod dijkstras;
+
+/// find the shortest path between two cells using the A* algorithm
+pub mod astar;
+
+/// a path through a maze, as returned by the solvers
+pub mod path;
+
+/// the route walked by the "human-style" solvers, including backtracks
+pub mod walk;
+
+/// solve a maze by keeping one hand on the wall
+pub mod wall_follower;
+
+/// solve a maze using Trémaux's algorithm, marking passages as they are walked
+pub mod tremaux;
+
+/// solve a maze by filling in its dead-ends
+pub mod dead_end_filling;
+
+/// solve a maze by wandering randomly
+pub mod random_mouse;
+
+/// find the longest path (the diameter) of a maze
+pub mod longest_path;
+pub use longest_path::longest_path;
+
+#[cfg(test)]
+mod property_tests;
@@ -0,0 +1,93 @@
+use crate::maze::grid_node::GridNode;
+use crate::solver::distances::Distances;
+
+/// Path is a route through a maze, as found by one of the solvers. It holds the nodes of the
+/// route in order, beginning with the start node and finishing with the goal node.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    nodes: Vec<GridNode>,
+    // the sum of the weights of every node on the path, except the start node
+    cost: i32,
+}
+
+impl Path {
+
+    /// returns a new Path through `nodes`, computing its cost from the weights of the nodes.
+    /// Moving into a node costs its weight, so the weight of the first node is not included. The
+    /// cost saturates at `i32::MAX` rather than overflowing
+    pub fn new(nodes: Vec<GridNode>) -> Self {
+        let cost = nodes.iter()
+            .skip(1)
+            .fold(0i32, |cost, node| cost.saturating_add(node.weight() as i32));
+        Self { nodes, cost }
+    }
+
+    /// returns the nodes on this path, in order from the start to the goal
+    pub fn nodes(&self) -> &[GridNode] {
+        &self.nodes
+    }
+
+    /// returns the total cost of moving along this path
+    pub fn cost(&self) -> i32 {
+        self.cost
+    }
+
+    /// returns the number of nodes on this path, including the start and goal
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// returns true if this path has no nodes
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// returns the first node of the path
+    pub fn start(&self) -> Option<GridNode> {
+        self.nodes.first().copied()
+    }
+
+    /// returns the last node of the path
+    pub fn goal(&self) -> Option<GridNode> {
+        self.nodes.last().copied()
+    }
+
+    /// returns a `Distances` struct, rooted at the start of this path, that holds the cost of
+    /// reaching every node on the path. This is the form used by `GridMaze::display_path`
+    /// and the exporters in the `formats` module. Returns `None` for an empty path
+    pub fn to_distances(&self) -> Option<Distances> {
+        let start = self.start()?;
+        let len = self.nodes.iter().map(|node| node.pos() + 1).max().unwrap_or(0);
+        let mut distances = Distances::with_capacity(start, len);
+        let mut cost = 0i32;
+        for node in self.nodes.iter().skip(1) {
+            cost = cost.saturating_add(node.weight() as i32);
+            distances.insert(*node, cost);
+        }
+        Some(distances)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::Path;
+    use crate::maze::grid_node::GridNode;
+
+    #[test]
+    fn should_compute_cost_without_start_weight() {
+        let path = Path::new(vec![GridNode::new(0, 9), GridNode::new(1, 2), GridNode::new(4, 3)]);
+        assert_eq!(path.cost(), 5);
+        assert_eq!(path.len(), 3);
+        assert_eq!(path.start(), Some(GridNode::new(0, 9)));
+        assert_eq!(path.goal(), Some(GridNode::new(4, 3)));
+
+        let distances = path.to_distances().unwrap();
+        assert_eq!(distances.root(), GridNode::new(0, 9));
+        assert_eq!(distances.get(&GridNode::new(0, 9)), Some(&0));
+        assert_eq!(distances.get(&GridNode::new(1, 2)), Some(&2));
+        assert_eq!(distances.get(&GridNode::new(4, 3)), Some(&5));
+        assert_eq!(distances.get(&GridNode::new(3, 1)), None);
+        assert!(Path::new(vec![]).to_distances().is_none());
+    }
+}
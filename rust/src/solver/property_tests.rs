@@ -0,0 +1,43 @@
+//! Property based tests that the shortest path agrees with the distances of a maze.
+
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use crate::generator::Algorithm;
+use crate::solver::dijkstras::{find_shortest_path, shortest_path};
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn shortest_path_should_be_contiguous_and_match_distances(
+        algo in prop::sample::select(Algorithm::ALL.to_vec()),
+        rows in 1usize..=40,
+        cols in 1usize..=40,
+        seed in any::<u64>(),
+        ends in (any::<prop::sample::Index>(), any::<prop::sample::Index>()),
+    ) {
+        let maze = algo.generate_with_rng(rows, cols, StdRng::seed_from_u64(seed)).unwrap();
+        let start = maze[ends.0.index(maze.len())];
+        let goal = maze[ends.1.index(maze.len())];
+
+        let path = shortest_path(&maze, start, goal);
+        prop_assert!(path.is_some(), "a perfect maze should connect every node");
+        let path = path.unwrap();
+        prop_assert_eq!(path.start(), Some(start));
+        prop_assert_eq!(path.goal(), Some(goal));
+        for step in path.nodes().windows(2) {
+            prop_assert!(maze.neighbors(&step[0]).contains(&step[1]));
+            prop_assert!(maze.has_link(&step[0], &step[1]));
+        }
+        let distances = maze.distances(&start);
+        prop_assert_eq!(path.cost(), distances[goal]);
+
+        // the distances of the path only hold the nodes on it, and count up to the goal
+        let on_path = find_shortest_path(&maze, start, goal).unwrap();
+        prop_assert_eq!(on_path[goal], path.cost());
+        for node in path.nodes() {
+            prop_assert_eq!(on_path.get(node), distances.get(node));
+        }
+    }
+}
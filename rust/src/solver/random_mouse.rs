@@ -0,0 +1,81 @@
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::walk::Walk;
+
+/// solves the `maze` like a mouse would, by wandering randomly from `start` until the `goal` is
+/// reached.
+///
+/// At each node the mouse picks a random passage, other than the one it came from, and only turns
+/// back when it reaches a dead-end. This will eventually find the goal of any maze, but it can
+/// take a very long time, so the walk stops after `max_steps` moves, with `reached_goal` false.
+pub fn solve<R: Rng>(maze: &GridMaze, start: GridNode, goal: GridNode, max_steps: usize, mut rng: R) -> Walk {
+    let mut nodes = vec![start];
+    let mut cur_node = start;
+    let mut came_from: Option<GridNode> = None;
+
+    while cur_node != goal {
+        if nodes.len() > max_steps {
+            return Walk::new(nodes, false);
+        }
+
+        let passages = maze.get_links(&cur_node);
+        let forwards: Vec<GridNode> = passages.iter()
+            .filter(|neighbor| Some(**neighbor) != came_from)
+            .copied()
+            .collect();
+        let next = match forwards.choose(&mut rng) {
+            Some(next_node) => *next_node,
+            // a dead-end, turn back if possible
+            None => match passages.first() {
+                Some(next_node) => *next_node,
+                None => return Walk::new(nodes, false),
+            },
+        };
+
+        came_from = Some(cur_node);
+        cur_node = next;
+        nodes.push(next);
+    }
+
+    Walk::new(nodes, true)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::solve;
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn should_eventually_find_the_goal() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let maze = algo.generate_with_rng(6, 6, StdRng::seed_from_u64(i as u64)).unwrap();
+            let (start, goal) = (maze[0], maze[maze.len() - 1]);
+            let walk = solve(&maze, start, goal, 1_000_000, StdRng::seed_from_u64(7));
+            assert!(walk.reached_goal, "{}", algo);
+            assert_eq!(walk.nodes.last(), Some(&goal));
+            for step in walk.nodes.windows(2) {
+                assert!(maze.has_link(&step[0], &step[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn should_stop_after_max_steps() {
+        let mut maze = GridMaze::new(1, 3).unwrap();
+        let (n0, n1) = (maze[0], maze[1]);
+        maze.link(&n0, &n1, true);
+        let walk = solve(&maze, n0, maze[2], 10, StdRng::seed_from_u64(1));
+        assert!(!walk.reached_goal);
+        assert_eq!(walk.steps, 10);
+
+        let unlinked = GridMaze::new(1, 3).unwrap();
+        let walk = solve(&unlinked, n0, unlinked[2], 10, StdRng::seed_from_u64(1));
+        assert_eq!(walk.steps, 0);
+    }
+}
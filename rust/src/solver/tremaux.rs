@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::walk::Walk;
+
+/// returns the key used to store the marks of the passage between `a` and `b`
+fn passage(a: &GridNode, b: &GridNode) -> (usize, usize) {
+    (a.pos().min(b.pos()), a.pos().max(b.pos()))
+}
+
+/// solves the `maze` using Trémaux's algorithm, walking from `start` until the `goal` is reached.
+///
+/// The walker marks every passage each time it walks along it, and never walks a passage that
+/// already has two marks. At every node it:
+/// 1. turns back, if it has come to a node it has visited before along a passage it hasn't
+///    walked before (i.e. it has found a loop)
+/// 2. else walks along the passage with the fewest marks, other than the one it came from
+/// 3. else turns back along the passage it came from
+///
+/// This works for any maze, including mazes with loops, and walks each passage at most twice.
+/// If the goal can't be reached the walker ends up back at the start, with nowhere left to go,
+/// and `reached_goal` is false. Ties between passages are broken in north, east, south, west order.
+pub fn solve(maze: &GridMaze, start: GridNode, goal: GridNode) -> Walk {
+    let mut marks: HashMap<(usize, usize), u8> = HashMap::new();
+    let mut nodes = vec![start];
+    let mut cur_node = start;
+    let mut came_from: Option<GridNode> = None;
+
+    while cur_node != goal {
+        let passages: Vec<GridNode> = maze.neighbors(&cur_node)
+            .into_iter()
+            .filter(|neighbor| maze.has_link(&cur_node, neighbor))
+            .collect();
+        let marks_of = |neighbor: &GridNode| *marks.get(&passage(&cur_node, neighbor)).unwrap_or(&0);
+
+        // a walker that can turn around is one that came along a passage it can walk back along
+        let back = came_from.filter(|prev| passages.contains(prev));
+        let visited_before = passages.iter()
+            .any(|neighbor| Some(*neighbor) != came_from && marks_of(neighbor) > 0);
+
+        let next = match back {
+            Some(prev) if visited_before && marks_of(&prev) == 1 => Some(prev),
+            _ => passages.iter()
+                .filter(|neighbor| Some(**neighbor) != came_from && marks_of(neighbor) < 2)
+                .min_by_key(|neighbor| marks_of(neighbor))
+                .copied()
+                .or_else(|| back.filter(|prev| marks_of(prev) < 2)),
+        };
+
+        match next {
+            Some(next_node) => {
+                *marks.entry(passage(&cur_node, &next_node)).or_insert(0) += 1;
+                came_from = Some(cur_node);
+                cur_node = next_node;
+                nodes.push(next_node);
+            }
+            None => return Walk::new(nodes, false),
+        }
+    }
+
+    Walk::new(nodes, true)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::solve;
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn should_solve_perfect_and_braided_mazes() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let mut maze = algo.generate_with_rng(10, 10, StdRng::seed_from_u64(i as u64)).unwrap();
+            if i % 2 == 1 {
+                maze.braid(1.0);
+            }
+            let (start, goal) = (maze[0], maze[maze.len() - 1]);
+            let passages: usize = maze.iter_nodes().map(|n| maze.get_links(n).len()).sum::<usize>() / 2;
+
+            let walk = solve(&maze, start, goal);
+            assert!(walk.reached_goal, "{}", algo);
+            assert_eq!(walk.nodes.last(), Some(&goal));
+            assert!(walk.steps <= 2 * passages);
+            for step in walk.nodes.windows(2) {
+                assert!(maze.has_link(&step[0], &step[1]));
+            }
+        }
+    }
+
+    #[test]
+    fn should_return_to_start_when_goal_is_unreachable() {
+        // two loops joined together, with the goal walled off
+        let mut maze = GridMaze::new(3, 3).unwrap();
+        for (a, b) in [(0, 1), (1, 2), (2, 5), (5, 4), (4, 1), (4, 3), (3, 0)].iter() {
+            let (n1, n2) = (maze[*a], maze[*b]);
+            maze.link(&n1, &n2, true);
+        }
+        let walk = solve(&maze, maze[0], maze[8]);
+        assert!(!walk.reached_goal);
+        assert_eq!(walk.nodes.last(), Some(&maze[0]));
+        // every passage was walked exactly twice
+        assert_eq!(walk.steps, 14);
+    }
+}
@@ -0,0 +1,25 @@
+use crate::maze::grid_node::GridNode;
+
+/// Walk is the route taken by one of the "human-style" solvers, such as the wall follower.
+/// Unlike a `Path`, a walk includes every dead-end explored and every step taken back out of it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Walk {
+    /// every node visited, in order, starting with the start node. A node appears again each time
+    /// the walk comes back to it
+    pub nodes: Vec<GridNode>,
+    /// the number of steps taken by the solver. For the walking solvers this is the number of
+    /// moves, i.e. one less than the number of nodes
+    pub steps: usize,
+    /// true if the walk ended at the goal. It is false if the solver gave up, ran out of steps
+    /// or the goal can't be reached
+    pub reached_goal: bool,
+}
+
+impl Walk {
+
+    /// returns a new Walk, where every move between the `nodes` counts as a step
+    pub(crate) fn new(nodes: Vec<GridNode>, reached_goal: bool) -> Self {
+        let steps = nodes.len().saturating_sub(1);
+        Self { nodes, steps, reached_goal }
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::HashSet;
+use crate::maze::grid_maze::GridMaze;
+use crate::maze::grid_node::GridNode;
+use crate::solver::walk::Walk;
+
+/// the hand kept on the wall while walking
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// returns the node in `direction` from `node`, where 0 = north, 1 = east, 2 = south and 3 = west
+fn step(maze: &GridMaze, node: &GridNode, direction: usize) -> Option<GridNode> {
+    match direction {
+        0 => maze.north(node),
+        1 => maze.east(node),
+        2 => maze.south(node),
+        _ => maze.west(node),
+    }
+}
+
+/// solves the `maze` by walking from `start` with one `hand` always touching a wall, until the
+/// `goal` is reached.
+///
+/// At each node the walker turns towards its hand if it can, else goes straight ahead, else
+/// turns away from its hand, else turns around. The walker starts facing north.
+///
+/// In a perfect maze this always reaches the goal. In a maze with loops the walker can circle
+/// an "island" of walls forever, so the walk stops, with `reached_goal` false, as soon as the
+/// walker is about to repeat a move it has already made.
+pub fn solve(maze: &GridMaze, start: GridNode, goal: GridNode, hand: Hand) -> Walk {
+    // the turns to try, relative to the direction being faced, in order of preference
+    let turns = match hand {
+        Hand::Left => [3, 0, 1, 2],
+        Hand::Right => [1, 0, 3, 2],
+    };
+
+    let mut nodes = vec![start];
+    let mut cur_node = start;
+    let mut facing = 0;
+    // every (position, direction) the walker has been at, to detect when it is going in circles
+    let mut seen = HashSet::new();
+
+    while cur_node != goal {
+        if !seen.insert((cur_node.pos(), facing)) {
+            return Walk::new(nodes, false);
+        }
+
+        let next = turns.iter()
+            .map(|turn| (facing + turn) % 4)
+            .find_map(|direction| {
+                step(maze, &cur_node, direction)
+                    .filter(|neighbor| maze.has_link(&cur_node, neighbor))
+                    .map(|neighbor| (direction, neighbor))
+            });
+
+        match next {
+            Some((direction, neighbor)) => {
+                facing = direction;
+                cur_node = neighbor;
+                nodes.push(neighbor);
+            }
+            // the start node has no passages out of it
+            None => return Walk::new(nodes, false),
+        }
+    }
+
+    Walk::new(nodes, true)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use super::{solve, Hand};
+    use crate::generator::Algorithm;
+    use crate::maze::grid_maze::GridMaze;
+
+    #[test]
+    fn should_solve_perfect_mazes_with_either_hand() {
+        for (i, algo) in Algorithm::ALL.iter().enumerate() {
+            let maze = algo.generate_with_rng(8, 8, StdRng::seed_from_u64(i as u64)).unwrap();
+            let (start, goal) = (maze[0], maze[maze.len() - 1]);
+            for hand in [Hand::Left, Hand::Right].iter() {
+                let walk = solve(&maze, start, goal, *hand);
+                assert!(walk.reached_goal, "{} with {:?} hand", algo, hand);
+                assert_eq!(walk.nodes.first(), Some(&start));
+                assert_eq!(walk.nodes.last(), Some(&goal));
+                // every passage of a perfect maze is walked at most twice
+                assert!(walk.steps <= 2 * (maze.len() - 1));
+                for step in walk.nodes.windows(2) {
+                    assert!(maze.has_link(&step[0], &step[1]));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_give_up_when_circling_an_island() {
+        // the walker starts on a loop around the center of a 3x3 maze, the goal is the center
+        let mut maze = GridMaze::new(3, 3).unwrap();
+        for (a, b) in [(0, 1), (1, 2), (2, 5), (5, 8), (8, 7), (7, 6), (6, 3), (3, 0)].iter() {
+            let (n1, n2) = (maze[*a], maze[*b]);
+            maze.link(&n1, &n2, true);
+        }
+        let walk = solve(&maze, maze[0], maze[4], Hand::Right);
+        assert!(!walk.reached_goal);
+        assert_eq!(walk.steps, 8);
+    }
+}